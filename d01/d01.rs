@@ -1,3 +1,6 @@
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
+
 fn level(s: &str) -> i32 {
     s.chars().fold(0, |acc, c| match c {
         '(' => acc + 1,
@@ -22,10 +25,24 @@ fn find_basement(s: &str) -> usize {
         .unwrap_or(0)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    println!("Part1: {}", level(input));
-    println!("Part2: {}", find_basement(input));
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = String;
+    type Part1 = i32;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(level(input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(find_basement(input))
+    }
 }
 
 #[cfg(test)]
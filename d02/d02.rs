@@ -1,7 +1,10 @@
+use crate::error::{Expected, number};
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
 use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
-struct Gift {
+pub struct Gift {
     l: i32,
     w: i32,
     h: i32,
@@ -14,18 +17,12 @@ impl Gift {
 }
 
 impl FromStr for Gift {
-    type Err = ();
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        || -> Option<Self> {
-            let mut it = s.split("x");
-            Some(Gift::new(
-                it.next()?.parse().ok()?,
-                it.next()?.parse().ok()?,
-                it.next()?.parse().ok()?,
-            ))
-        }()
-        .ok_or(())
+        let mut it = s.split("x");
+        let mut next = || number(s, it.next().ok_or_else(|| Expected::end(s, "`x`"))?);
+        Ok(Gift::new(next()?, next()?, next()?))
     }
 }
 
@@ -56,13 +53,24 @@ fn required_ribbon(gift: Gift) -> i32 {
     shortest_circumference(gift) + cuboid_volume(gift)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let gifts: Vec<Gift> = input.lines().map(str::parse).map(Result::unwrap).collect();
-    let wrapping: i32 = gifts.iter().copied().map(required_wrapping).sum();
-    println!("Part1: {wrapping}");
-    let ribbon: i32 = gifts.iter().copied().map(required_ribbon).sum();
-    println!("Part2: {ribbon}");
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Gift>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines()?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.iter().copied().map(required_wrapping).sum())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.iter().copied().map(required_ribbon).sum())
+    }
 }
 
 #[cfg(test)]
@@ -73,9 +81,10 @@ mod tests {
     fn test_parse() {
         assert_eq!("1x2x3".parse(), Ok(Gift::new(1, 2, 3)));
         assert_eq!("1x2x3x4".parse(), Ok(Gift::new(1, 2, 3)));
-        assert_eq!("1x2".parse(), Err::<Gift, ()>(()));
-        assert_eq!("xxx".parse(), Err::<Gift, ()>(()));
-        assert_eq!("".parse(), Err::<Gift, ()>(()));
+        assert_eq!("1x2".parse::<Gift>(), Err(Expected::new(3, "`x`")));
+        assert_eq!("1x2xa".parse::<Gift>(), Err(Expected::new(4, "a number")));
+        assert_eq!("xxx".parse::<Gift>(), Err(Expected::new(0, "a number")));
+        assert_eq!("".parse::<Gift>(), Err(Expected::new(0, "a number")));
     }
 
     #[test]
@@ -1,3 +1,5 @@
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
 use std::collections::HashSet;
 use vecmath::Vector2;
 
@@ -58,12 +60,24 @@ fn visited_robo_houses(instructions: &str) -> HashSet<Vector2<i32>> {
         .collect()
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let visit_count = visited_houses(input).len();
-    println!("Part1: {}", visit_count);
-    let robo_visit_count = visited_robo_houses(input).len();
-    println!("Part2: {}", robo_visit_count);
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(visited_houses(input).len())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(visited_robo_houses(input).len())
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
 use md5::{Digest, Md5};
 
 fn find_number1(input: &str) -> u64 {
@@ -24,10 +26,22 @@ fn find_number2(input: &str) -> u64 {
     panic!();
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let number = find_number1(input);
-    println!("Part1: {number}");
-    let number = find_number2(input);
-    println!("Part2: {number}");
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = String;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(find_number1(input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(find_number2(input))
+    }
 }
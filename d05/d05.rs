@@ -1,3 +1,6 @@
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
+
 fn contains_3vowels(s: &str) -> bool {
     let mut vowel_count = 0;
     for c in s.chars() {
@@ -45,12 +48,24 @@ fn is_nice2(s: &str) -> bool {
     contains_repeating_pair(s) && contains_separated_repeat(s)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let count = input.lines().filter(|l| is_nice1(l)).count();
-    println!("Part1: {count}");
-    let count = input.lines().filter(|l| is_nice2(l)).count();
-    println!("Part2: {count}");
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.iter().filter(|l| is_nice1(l)).count())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.iter().filter(|l| is_nice2(l)).count())
+    }
 }
 
 #[cfg(test)]
@@ -1,90 +1,62 @@
-#![feature(assert_matches)]
-
-use anyhow::anyhow;
-use std::ops::{Index, IndexMut};
+use crate::error::{Expected, number};
+use crate::grid::{Grid, Pos};
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
 use std::str::FromStr;
-use vecmath::Vector2;
 
-type Pos = Vector2<i32>;
+const SIZE: usize = 1000;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     On(Pos, Pos),
     Off(Pos, Pos),
     Toggle(Pos, Pos),
 }
 
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_pos(p: &str) -> Result<Pos, anyhow::Error> {
-            let mut it = p.split(',');
-            Ok([
-                it.next().ok_or_else(|| anyhow!("expected int"))?.parse()?,
-                it.next().ok_or_else(|| anyhow!("expected int"))?.parse()?,
-            ])
-        }
         let mut tokens = s.split(' ');
-        let cons = match tokens.next() {
-            Some("turn") => match tokens.next() {
-                Some("on") => Instruction::On,
-                Some("off") => Instruction::Off,
-                _ => return Err(anyhow!("expected on|off")),
+        let mut next = |what: &str| tokens.next().ok_or_else(|| Expected::end(s, what));
+        let parse_pos = |p: &str| -> Result<Pos, Expected> {
+            let (x, y) = p
+                .split_once(',')
+                .ok_or_else(|| Expected::at(s, p, "`x,y`"))?;
+            let coordinate = |c: &str| match number(s, c)? {
+                c if c < SIZE => Ok(c),
+                _ => Err(Expected::at(s, c, format!("a coordinate below {SIZE}"))),
+            };
+            Ok([coordinate(x)?, coordinate(y)?])
+        };
+        let cons = match next("`turn` or `toggle`")? {
+            "turn" => match next("`on` or `off`")? {
+                "on" => Instruction::On,
+                "off" => Instruction::Off,
+                token => return Err(Expected::at(s, token, "`on` or `off`")),
             },
-            Some("toggle") => Instruction::Toggle,
-            _ => return Err(anyhow!("expected turn|toggle")),
+            "toggle" => Instruction::Toggle,
+            token => return Err(Expected::at(s, token, "`turn` or `toggle`")),
         };
-        let from = parse_pos(tokens.next().ok_or_else(|| anyhow!("expected pos"))?)?;
-        if !matches!(tokens.next(), Some("through")) {
-            return Err(anyhow!("expected through"));
+        let from = parse_pos(next("a position")?)?;
+        match next("`through`")? {
+            "through" => {}
+            token => return Err(Expected::at(s, token, "`through`")),
         }
-        let to = parse_pos(tokens.next().ok_or_else(|| anyhow!("expected pos"))?)?;
+        let to = parse_pos(next("a position")?)?;
         Ok(cons(from, to))
     }
 }
 
-struct Grid<T>(Vec<T>);
-
-impl Grid<bool> {
-    fn new() -> Grid<bool> {
-        Grid(vec![false; 1_000_000])
-    }
-}
-
-impl Grid<u32> {
-    fn new() -> Grid<u32> {
-        Grid(vec![0; 1_000_000])
-    }
-}
-
-impl<T> Index<usize> for Grid<T> {
-    type Output = <Vec<T> as Index<usize>>::Output;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        Index::index(&self.0, index)
-    }
-}
-
-impl<T> IndexMut<usize> for Grid<T> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        IndexMut::index_mut(&mut self.0, index)
-    }
-}
-
 fn execute_instruction1(mut grid: Grid<bool>, ins: Instruction) -> Grid<bool> {
     use Instruction::*;
     let (On(from, to) | Off(from, to) | Toggle(from, to)) = ins;
-    for y in from[0]..=to[0] {
-        for x in from[1]..=to[1] {
-            let x = x as usize;
-            let y = y as usize;
-            match ins {
-                On(..) => grid[y * 1000 + x] = true,
-                Off(..) => grid[y * 1000 + x] = false,
-                Toggle(..) => grid[y * 1000 + x] ^= true,
-            };
-        }
+    for light in grid.rect_mut(from, to) {
+        match ins {
+            On(..) => *light = true,
+            Off(..) => *light = false,
+            Toggle(..) => *light ^= true,
+        };
     }
     grid
 }
@@ -92,46 +64,48 @@ fn execute_instruction1(mut grid: Grid<bool>, ins: Instruction) -> Grid<bool> {
 fn execute_instruction2(mut grid: Grid<u32>, ins: Instruction) -> Grid<u32> {
     use Instruction::*;
     let (On(from, to) | Off(from, to) | Toggle(from, to)) = ins;
-    for y in from[0]..=to[0] {
-        for x in from[1]..=to[1] {
-            let x = x as usize;
-            let y = y as usize;
-            match ins {
-                On(..) => grid[y * 1000 + x] += 1,
-                Off(..) => {
-                    grid[y * 1000 + x] = if grid[y * 1000 + x] != 0 {
-                        grid[y * 1000 + x] - 1
-                    } else {
-                        grid[y * 1000 + x]
-                    }
-                }
-                Toggle(..) => grid[y * 1000 + x] += 2,
-            };
-        }
+    for light in grid.rect_mut(from, to) {
+        match ins {
+            On(..) => *light += 1,
+            Off(..) => *light = light.saturating_sub(1),
+            Toggle(..) => *light += 2,
+        };
     }
     grid
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let result = input
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .fold(Grid::<bool>::new(), execute_instruction1);
-    let count = result.0.iter().filter(|&&x| x).count();
-    println!("Part1: {}", count);
-    let result = input
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .fold(Grid::<u32>::new(), execute_instruction2);
-    let count: u32 = result.0.iter().sum();
-    println!("Part2: {}", count);
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<Instruction>;
+    type Part1 = usize;
+    type Part2 = u32;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines()?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let result = input
+            .iter()
+            .copied()
+            .fold(Grid::new(SIZE, SIZE, false), execute_instruction1);
+        Ok(result.iter().filter(|&&x| x).count())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let result = input
+            .iter()
+            .copied()
+            .fold(Grid::new(SIZE, SIZE, 0), execute_instruction2);
+        Ok(result.iter().sum())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::assert_matches::assert_matches;
+    use std::assert_matches;
 
     #[test]
     fn test_parse_instruction() {
@@ -148,5 +122,17 @@ mod tests {
             "turn off 499,499 through 500,500".parse(),
             Ok(Instruction::Off([499, 499], [500, 500]))
         );
+        assert_eq!(
+            "turn on 0,0 thru 999,999".parse::<Instruction>(),
+            Err(Expected::new(12, "`through`"))
+        );
+        assert_eq!(
+            "toggle 0,x through 999,0".parse::<Instruction>(),
+            Err(Expected::new(9, "a number"))
+        );
+        assert_eq!(
+            "toggle 0,0 through 1000,0".parse::<Instruction>(),
+            Err(Expected::new(19, "a coordinate below 1000"))
+        );
     }
 }
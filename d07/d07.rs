@@ -1,5 +1,8 @@
-#![feature(assert_matches)]
-
+use crate::input::PuzzleInput;
+use crate::parse::parse_all;
+use crate::repl::{Command, Explore, arg};
+use crate::solution::Solution;
+use anyhow::{Context, bail};
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::fmt::Formatter;
@@ -69,7 +72,7 @@ impl fmt::Display for Expression {
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Wiring {
+pub struct Wiring {
     expression: Expression,
     output: String,
 }
@@ -82,58 +85,42 @@ impl fmt::Display for Wiring {
 
 mod grammar {
     use super::*;
-    use nom::IResult;
+    use crate::parse::{PResult, expect, keyword, letters, token, uint, ws};
     use nom::Parser;
     use nom::branch::alt;
-    use nom::bytes::tag;
-    use nom::character::complete::{alpha1, digit1, space0};
-    use nom::combinator::{map, map_res, not, peek, value, verify};
-    use nom::error::ParseError;
-    use nom::sequence::{preceded, terminated};
+    use nom::combinator::{map, value, verify};
 
     const RESERVED: &[&str] = &["AND", "OR", "LSHIFT", "RSHIFT", "NOT"];
 
-    pub fn ws<I, O, E: ParseError<I>, G>(inner: G) -> impl Parser<I, Output = O, Error = E>
-    where
-        G: Parser<I, Output = O, Error = E>,
-        I: nom::Input,
-        <I as nom::Input>::Item: nom::AsChar,
-    {
-        preceded(space0, inner)
-    }
-
-    fn parse_unop(i: &str) -> IResult<&str, Unop> {
-        terminated(value(Unop::Not, tag("NOT")), not(peek(alpha1))).parse(i)
+    fn parse_unop(i: &str) -> PResult<'_, Unop> {
+        value(Unop::Not, keyword("NOT")).parse(i)
     }
 
-    fn parse_binop(i: &str) -> IResult<&str, Binop> {
-        terminated(
-            alt((
-                value(Binop::And, tag("AND")),
-                value(Binop::Or, tag("OR")),
-                value(Binop::LShift, tag("LSHIFT")),
-                value(Binop::RShift, tag("RSHIFT")),
-            )),
-            not(peek(alpha1)),
-        )
+    fn parse_binop(i: &str) -> PResult<'_, Binop> {
+        alt((
+            value(Binop::And, keyword("AND")),
+            value(Binop::Or, keyword("OR")),
+            value(Binop::LShift, keyword("LSHIFT")),
+            value(Binop::RShift, keyword("RSHIFT")),
+        ))
         .parse(i)
     }
 
-    fn parse_value(i: &str) -> IResult<&str, Value> {
-        alt((
-            map(map_res(digit1, str::parse), Value::Lit),
-            map(
+    fn parse_value(i: &str) -> PResult<'_, Value> {
+        expect(
+            "a number or wire",
+            alt((
+                map(uint, Value::Lit),
                 map(
-                    verify(alpha1, |ident| !RESERVED.contains(ident)),
-                    String::from,
+                    verify(letters, |ident: &str| !RESERVED.contains(&ident)),
+                    |ident| Value::Var(ident.to_string()),
                 ),
-                Value::Var,
-            ),
-        ))
+            )),
+        )
         .parse(i)
     }
 
-    fn parse_expression(i: &str) -> IResult<&str, Expression> {
+    fn parse_expression(i: &str) -> PResult<'_, Expression> {
         if let Ok((i2, op)) = parse_unop(i) {
             return ws(map(parse_value, |v| Expression::Unary(op, v))).parse(i2);
         }
@@ -146,17 +133,12 @@ mod grammar {
         }
     }
 
-    pub fn parse_wiring(i: &str) -> IResult<&str, Wiring> {
+    pub fn parse_wiring(i: &str) -> PResult<'_, Wiring> {
         map(
-            (
-                ws(parse_expression),
-                ws(tag("->")),
-                ws(map(alpha1, String::from)),
-                space0,
-            ),
-            |(e, _, name, _)| Wiring {
+            (ws(parse_expression), ws(token("->")), ws(letters)),
+            |(e, _, name)| Wiring {
                 expression: e,
-                output: name,
+                output: name.to_string(),
             },
         )
         .parse(i)
@@ -165,14 +147,18 @@ mod grammar {
     #[cfg(test)]
     mod tests {
         use super::*;
-        use std::assert_matches::assert_matches;
+        use crate::parse::Error;
+        use std::assert_matches;
 
         #[test]
         fn test_unop() {
             assert_eq!(parse_unop("NOT"), Ok(("", Unop::Not)));
             assert_eq!(parse_unop("NOT t"), Ok((" t", Unop::Not)));
             assert_matches!(parse_unop("NOTt"), Err(nom::Err::Error(_)));
-            assert_matches!(parse_unop("NO"), Err(nom::Err::Incomplete(_)));
+            assert_eq!(
+                parse_unop("NO"),
+                Err(nom::Err::Error(Error::new("NO", "`NOT`")))
+            );
         }
 
         #[test]
@@ -195,10 +181,7 @@ mod grammar {
             assert_eq!(parse_value("asd"), Ok(("", Value::Var("asd".to_string()))));
             assert_eq!(
                 parse_value("NOT"),
-                Err(nom::Err::Error(nom::error::Error::new(
-                    "NOT",
-                    nom::error::ErrorKind::Verify
-                )))
+                Err(nom::Err::Error(Error::new("NOT", "a number or wire")))
             );
             assert_eq!(
                 parse_value("NOTt"),
@@ -271,47 +254,129 @@ fn eval_wirings<'a>(
     wirings: &'a HashMap<String, Wiring>,
     values: &mut HashMap<&'a str, u16>,
     goal: &'a str,
-) -> Option<u16> {
+) -> anyhow::Result<u16> {
     let mut evaluation_queue = VecDeque::from([goal]);
     while let Some(&next) = evaluation_queue.front() {
         if values.contains_key(next) {
             evaluation_queue.pop_front();
             continue;
         }
-        let wiring = &wirings[next];
+        let Some(wiring) = wirings.get(next) else {
+            bail!("wire `{next}` is never driven");
+        };
         match eval_expr(&wiring.expression, values) {
             Ok(i) => {
-                println!("{next} evaluated to {i}");
+                log::trace!("{next} evaluated to {i}");
                 values.insert(next, i);
                 evaluation_queue.pop_front();
             }
             Err(requirements) => {
-                println!("{next} requires {requirements:?}");
+                log::trace!("{next} requires {requirements:?}");
                 for r in requirements {
                     evaluation_queue.push_front(r);
                 }
             }
         };
     }
-    values.get(goal).copied()
+    values
+        .get(goal)
+        .copied()
+        .with_context(|| format!("{goal} has no value"))
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let wirings: HashMap<String, Wiring> = input
-        .lines()
-        .map(|line| {
-            grammar::parse_wiring(line)
-                .expect("Failed to parse wiring")
-                .1
+/// The wirings, and the wires that the REPL set to a value instead, like `b` in part 2.
+pub struct Circuit {
+    wirings: HashMap<String, Wiring>,
+    overrides: HashMap<String, u16>,
+}
+
+impl Explore for Circuit {
+    const COMMANDS: &'static [Command<Self>] = &[
+        Command {
+            name: "eval",
+            args: "WIRE",
+            help: "The signal on a wire.",
+            run: |c, args| {
+                let wire: String = arg(args, 0, "wire")?;
+                if !c.wirings.contains_key(&wire) && !c.overrides.contains_key(&wire) {
+                    bail!("no wire {wire}");
+                }
+                let mut values = c.overrides.iter().map(|(w, v)| (w.as_str(), *v)).collect();
+                let value = eval_wirings(&c.wirings, &mut values, &wire)?;
+                Ok(value.to_string())
+            },
+        },
+        Command {
+            name: "wire",
+            args: "WIRE",
+            help: "Where the signal on a wire comes from.",
+            run: |c, args| {
+                let wire: String = arg(args, 0, "wire")?;
+                match (c.overrides.get(&wire), c.wirings.get(&wire)) {
+                    (Some(value), _) => Ok(format!("{value} -> {wire} (set)")),
+                    (None, Some(wiring)) => Ok(wiring.to_string()),
+                    (None, None) => bail!("no wire {wire}"),
+                }
+            },
+        },
+        Command {
+            name: "set",
+            args: "WIRE VALUE",
+            help: "Overrides a wire with a signal.",
+            run: |c, args| {
+                let wire: String = arg(args, 0, "wire")?;
+                let value = arg(args, 1, "signal")?;
+                c.overrides.insert(wire.clone(), value);
+                Ok(format!("{value} -> {wire}"))
+            },
+        },
+        Command {
+            name: "unset",
+            args: "WIRE",
+            help: "Takes the override off a wire again.",
+            run: |c, args| {
+                let wire: String = arg(args, 0, "wire")?;
+                match c.overrides.remove(&wire) {
+                    Some(_) => Ok(c
+                        .wirings
+                        .get(&wire)
+                        .map_or(String::new(), Wiring::to_string)),
+                    None => bail!("{wire} isn't set"),
+                }
+            },
+        },
+    ];
+
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self> {
+        Ok(Circuit {
+            wirings: Day07::parse(input)?,
+            overrides: HashMap::new(),
         })
-        .map(|wiring| (wiring.output.clone(), wiring))
-        .collect();
-    let a1 = eval_wirings(&wirings, &mut HashMap::new(), "a").expect("a1");
-    let a2 = eval_wirings(&wirings, &mut HashMap::from([("b", a1)]), "a").expect("a2");
+    }
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = HashMap<String, Wiring>;
+    type Part1 = u16;
+    type Part2 = u16;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        let wirings = input.parse_lines_with(|line| {
+            parse_all(line, grammar::parse_wiring).map(|wiring| (wiring.output.clone(), wiring))
+        })?;
+        Ok(wirings.into_iter().collect())
+    }
 
-    println!("Par1: {a1}");
-    println!("Par2: {a2}");
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        eval_wirings(input, &mut HashMap::new(), "a")
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let a1 = Self::part1(input)?;
+        eval_wirings(input, &mut HashMap::from([("b", a1)]), "a")
+    }
 }
 
 #[cfg(test)]
@@ -341,4 +406,37 @@ mod tests {
         let result = eval_expr(&expr, &values);
         assert_eq!(result, Err(vec!["asd"]));
     }
+
+    #[test]
+    fn test_circuit() {
+        use crate::repl::Session;
+        let input = PuzzleInput::new("123 -> x\nx AND y -> d\n456 -> y\nNOT x -> h\n", "");
+        let mut circuit = Circuit::load(&input).unwrap();
+        let mut call = |line: &str| {
+            let words: Vec<_> = line.split(' ').collect();
+            circuit.call(words[0], &words[1..]).unwrap()
+        };
+        assert_eq!(call("eval d").unwrap(), "72");
+        assert_eq!(call("wire h").unwrap(), "NOT x -> h");
+        assert_eq!(call("set x 0").unwrap(), "0 -> x");
+        assert_eq!(call("eval h").unwrap(), "65535");
+        assert_eq!(call("unset x").unwrap(), "123 -> x");
+        assert_eq!(call("eval h").unwrap(), "65412");
+        assert_eq!(call("eval q").unwrap_err().to_string(), "no wire q");
+    }
+
+    #[test]
+    fn test_circuit_undriven_wire() {
+        use crate::repl::Session;
+        let input = PuzzleInput::new(
+            "x AND y -> d
+123 -> x
+",
+            "",
+        );
+        let mut circuit = Circuit::load(&input).unwrap();
+        let e = circuit.call("eval", &["d"]).unwrap().unwrap_err();
+        assert_eq!(e.to_string(), "wire `y` is never driven");
+        assert_eq!(circuit.call("eval", &["x"]).unwrap().unwrap(), "123");
+    }
 }
@@ -1,3 +1,6 @@
+use crate::input::PuzzleInput;
+use crate::parse::parse_all;
+use crate::solution::Solution;
 use nom::branch::alt;
 use nom::bytes::streaming::{is_not, take_while_m_n};
 use nom::character::streaming::{char, multispace1};
@@ -85,18 +88,41 @@ where
     delimited(char('"'), build_string, char('"')).parse(input)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let mut count = 0usize;
-    for l in input.lines() {
-        count += l.trim().len() - parse_string::<()>(l.trim()).unwrap().1.len();
+/// A string literal as written in the code, and the bytes it stands for.
+pub struct Literal {
+    code: String,
+    decoded: Vec<u8>,
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Vec<Literal>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines_with(|line| {
+            let code = line.trim();
+            let decoded = parse_all(code, parse_string).map_err(|e| e.within(line, code))?;
+            Ok(Literal {
+                code: code.to_string(),
+                decoded,
+            })
+        })?)
     }
-    println!("Par1: {}", count);
-    let mut count = 0usize;
-    for l in input.lines() {
-        count += l.trim().replace('\\', "\\\\").replace('"', "\\\"").len() + 2 - l.trim().len();
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.iter().map(|l| l.code.len() - l.decoded.len()).sum())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let mut count = 0usize;
+        for Literal { code: l, .. } in input {
+            count += l.replace('\\', "\\\\").replace('"', "\\\"").len() + 2 - l.len();
+        }
+        Ok(count)
     }
-    println!("Par2: {}", count);
 }
 
 #[cfg(test)]
@@ -1,77 +1,160 @@
-use aoc2015::{inv_tsp, tsp};
+use crate::error::{Expected, number};
+use crate::graph::k_shortest_paths_rev;
+use crate::input::PuzzleInput;
+use crate::solution::{Answer, Solution};
+use crate::{inv_tsp_route, tsp_route};
+use serde_json::{Value, json};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Formatter;
 use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct Edge(String, String, i32);
 
 impl FromStr for Edge {
-    type Err = ();
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        || -> Option<Self> {
-            let mut words = s.split(' ');
-            let a = words.next()?.to_string();
-            words.next()?;
-            let b = words.next()?.to_string();
-            words.next()?;
-            let d: i32 = words.next()?.parse().ok()?;
-            Some(Edge(a, b, d))
-        }()
-        .ok_or(())
+        let mut words = s.split(' ');
+        let mut next = |what: &str| words.next().ok_or_else(|| Expected::end(s, what));
+        let a = next("a city")?.to_string();
+        next("`to`")?;
+        let b = next("a city")?.to_string();
+        next("`=`")?;
+        let d = number(s, next("a distance")?)?;
+        Ok(Edge(a, b, d))
     }
 }
 
-fn main() {
-    // let input = include_str!("sample.txt");
-    let input = include_str!("input.txt");
-    let edges: Vec<Edge> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-    let cities = edges
-        .iter()
-        .flat_map(|e| [e.0.as_str(), e.1.as_str()])
-        .collect::<HashSet<_>>()
+/// Distances between all cities, with an extra city at index 0 that is 0 away from every other
+/// city, so that the round trip found by [tsp_route] can start and end anywhere.
+pub struct Distances {
+    n: usize,
+    cities: Vec<String>,
+    edge_weights: Vec<i32>,
+}
+
+impl Distances {
+    fn get(&self, a: u16, b: u16) -> i32 {
+        self.edge_weights[b as usize * self.n + a as usize]
+    }
+
+    /// Turns a round trip from [tsp_route] into the cities visited, leaving out the extra city.
+    fn route(&self, (distance, route): (i32, Vec<u16>)) -> Route {
+        Route {
+            distance,
+            cities: route[1..]
+                .iter()
+                .map(|&i| self.cities[i as usize - 1].clone())
+                .collect(),
+        }
+    }
+
+    /// The `k` shortest routes, shortest first. A route and the same one backwards both count.
+    pub fn shortest_routes(&self, k: usize) -> Vec<Route> {
+        let all = (1u64 << self.n) - 1;
+        // (cities visited so far, the last one), starting at the extra city.
+        let neighbors = |&(visited, last): &(u64, u16)| {
+            (1..self.n as u16)
+                .filter(move |&next| visited & (1 << next) == 0 && self.get(last, next) != i32::MAX)
+                .map(move |next| ((visited | (1 << next), next), next))
+        };
+        k_shortest_paths_rev(
+            &(1, 0),
+            |&(visited, _)| visited == all,
+            neighbors,
+            |&(_, last), &next, _| self.get(last, next) as i64,
+            k,
+        )
         .into_iter()
-        .collect::<Vec<_>>();
-    let city_index = cities
-        .iter()
-        .enumerate()
-        .map(|(i, &n)| (n, i))
-        .collect::<HashMap<_, _>>();
-
-    let n = cities.len() + 1;
-
-    let mut edge_weights = vec![i32::MAX; n * n];
-    let idx = |x: usize, y: usize| y * n + x;
-    for i in 0..n {
-        edge_weights[idx(0, i)] = 0;
-        edge_weights[idx(i, 0)] = 0;
+        .map(|path| {
+            let cities = path.path_rev.iter().rev().map(|(_, next)| *next);
+            self.route((path.cost as i32, [0].into_iter().chain(cities).collect()))
+        })
+        .collect()
     }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Route {
+    pub distance: i32,
+    pub cities: Vec<String>,
+}
 
-    for e in &edges {
-        let i0 = city_index[e.0.as_str()] + 1;
-        let i1 = city_index[e.1.as_str()] + 1;
-        edge_weights[idx(i0, i1)] = e.2;
-        edge_weights[idx(i1, i0)] = e.2;
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.distance)
     }
+}
 
-    println!("{:?}", cities);
-    println!("{:?}", city_index);
-    println!("{:?}", edge_weights);
+impl Answer for Route {
+    fn artifacts(&self) -> Option<Value> {
+        Some(json!({ "route": self.cities }))
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Distances;
+    type Part1 = Route;
+    type Part2 = Route;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        let edges: Vec<Edge> = input.parse_lines()?;
+        let cities = edges
+            .iter()
+            .flat_map(|e| [e.0.as_str(), e.1.as_str()])
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let city_index = cities
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| (n, i))
+            .collect::<HashMap<_, _>>();
+
+        let n = cities.len() + 1;
+
+        let mut edge_weights = vec![i32::MAX; n * n];
+        let idx = |x: usize, y: usize| y * n + x;
+        for i in 0..n {
+            edge_weights[idx(0, i)] = 0;
+            edge_weights[idx(i, 0)] = 0;
+        }
+
+        for e in &edges {
+            let i0 = city_index[e.0.as_str()] + 1;
+            let i1 = city_index[e.1.as_str()] + 1;
+            edge_weights[idx(i0, i1)] = e.2;
+            edge_weights[idx(i1, i0)] = e.2;
+        }
+
+        log::debug!("cities: {cities:?}");
+        log::debug!("city indices: {city_index:?}");
+        log::trace!("edge weights: {edge_weights:?}");
+
+        Ok(Distances {
+            n,
+            cities: cities.into_iter().map(String::from).collect(),
+            edge_weights,
+        })
+    }
 
-    let shortest = tsp(n as u16, |a, b| edge_weights[idx(a as usize, b as usize)]);
-    println!("Shortest: {}", shortest);
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.route(tsp_route(input.n as u16, |a, b| input.get(a, b))))
+    }
 
-    let longest = inv_tsp(n as u16, |a, b| edge_weights[idx(a as usize, b as usize)]);
-    println!("Longest: {}", longest);
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.route(inv_tsp_route(input.n as u16, |a, b| input.get(a, b))))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tsp;
 
     #[test]
     fn test_parse() {
@@ -83,10 +166,61 @@ mod tests {
             "London to Dublin = 464 ignored extra stuff".parse(),
             Ok(Edge("London".to_string(), "Dublin".to_string(), 464))
         );
-        assert_eq!("London to Dublin = asd".parse(), Err::<Edge, ()>(()));
-        assert_eq!("London to Dublin = ".parse(), Err::<Edge, ()>(()));
-        assert_eq!("London to Dublin =".parse(), Err::<Edge, ()>(()));
-        assert_eq!("".parse(), Err::<Edge, ()>(()));
+        assert_eq!(
+            "London to Dublin = asd".parse::<Edge>(),
+            Err(Expected::new(19, "a number"))
+        );
+        assert_eq!(
+            "London to Dublin = ".parse::<Edge>(),
+            Err(Expected::new(19, "a number"))
+        );
+        assert_eq!(
+            "London to Dublin =".parse::<Edge>(),
+            Err(Expected::new(18, "a distance"))
+        );
+        assert_eq!("".parse::<Edge>(), Err(Expected::new(0, "`to`")));
+    }
+
+    #[test]
+    fn test_route() {
+        let input = PuzzleInput::new(
+            "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141\n",
+            "",
+        );
+        let distances = Day09::parse(&input).unwrap();
+        let shortest = Day09::part1(&distances).unwrap();
+        assert_eq!(shortest.distance, 605);
+        assert!(
+            shortest.cities == ["London", "Dublin", "Belfast"]
+                || shortest.cities == ["Belfast", "Dublin", "London"]
+        );
+        let longest = Day09::part2(&distances).unwrap();
+        assert_eq!(longest.distance, 982);
+        assert_eq!(longest.cities[1], "London");
+        assert_eq!(
+            longest.artifacts().unwrap()["route"]
+                .as_array()
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_shortest_routes() {
+        let input = PuzzleInput::new(
+            "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141\n",
+            "",
+        );
+        let distances = Day09::parse(&input).unwrap();
+        let routes = distances.shortest_routes(4);
+        assert_eq!(
+            routes.iter().map(|r| r.distance).collect::<Vec<_>>(),
+            vec![605, 605, 659, 659]
+        );
+        assert_eq!(routes[0].cities.len(), 3);
+        assert_ne!(routes[0].cities, routes[1].cities);
+        assert_eq!(distances.shortest_routes(10).len(), 6);
     }
 
     #[test]
@@ -1,3 +1,5 @@
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
 use itertools::Itertools;
 
 fn look_say(i: &str) -> String {
@@ -8,11 +10,24 @@ fn look_say(i: &str) -> String {
         .collect()
 }
 
-fn main() {
-    let result = (0..40).fold("1113222113".to_string(), |s, _| look_say(&s));
-    println!("Part1: {}", result.len());
-    let result = (0..10).fold(result, |s, _| look_say(&s));
-    println!("Part2: {}", result.len());
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok((0..40).fold(input.clone(), |s, _| look_say(&s)).len())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok((0..50).fold(input.clone(), |s, _| look_say(&s)).len())
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
-#![feature(iter_map_windows)]
-
+use crate::input::PuzzleInput;
+use crate::repl::{Command, Explore};
+use crate::solution::Solution;
 use itertools::Itertools;
 
 fn increment(s: impl Into<Vec<u8>>) -> Vec<u8> {
@@ -53,11 +54,75 @@ fn next_safe_password(s: impl AsRef<[u8]>) -> Vec<u8> {
     }
 }
 
-fn main() {
-    let result: String = String::from_utf8(next_safe_password(b"hxbxwxba")).unwrap();
-    println!("Part1: {}", result);
-    let result: String = String::from_utf8(next_safe_password(increment(result))).unwrap();
-    println!("Part2: {}", result);
+/// The password from the input, for the REPL commands that aren't given one.
+pub struct Passwords(String);
+
+impl Passwords {
+    fn password<'a>(&'a self, args: &[&'a str]) -> &'a str {
+        args.first().copied().unwrap_or(&self.0)
+    }
+}
+
+impl Explore for Passwords {
+    const COMMANDS: &'static [Command<Self>] = &[
+        Command {
+            name: "safe",
+            args: "[PASSWORD]",
+            help: "Whether the password follows all the rules.",
+            run: |p, args| Ok(password_is_safe(p.password(args)).to_string()),
+        },
+        Command {
+            name: "rules",
+            args: "[PASSWORD]",
+            help: "Checks the rules one by one.",
+            run: |p, args| {
+                let s = p.password(args);
+                Ok(format!(
+                    "straight: {}, no i, o or l: {}, pairs: {}",
+                    contains_straight(s),
+                    !contains_illegal(s),
+                    distinct_pair_count(s)
+                ))
+            },
+        },
+        Command {
+            name: "increment",
+            args: "[PASSWORD]",
+            help: "The password after this one.",
+            run: |p, args| Ok(String::from_utf8(increment(p.password(args)))?),
+        },
+        Command {
+            name: "next",
+            args: "[PASSWORD]",
+            help: "The next safe password, starting with this one.",
+            run: |p, args| Ok(String::from_utf8(next_safe_password(p.password(args)))?),
+        },
+    ];
+
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self> {
+        Ok(Passwords(Day11::parse(input)?))
+    }
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = String;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(String::from_utf8(next_safe_password(input))?)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let first = next_safe_password(input);
+        Ok(String::from_utf8(next_safe_password(increment(first)))?)
+    }
 }
 
 #[cfg(test)]
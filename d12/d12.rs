@@ -1,5 +1,6 @@
-#![feature(assert_matches)]
-
+use crate::error::Expected;
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
 use serde::de::{Error, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer};
 use std::fmt;
@@ -195,18 +196,35 @@ impl<'de> Deserialize<'de> for NumberSumRed {
     }
 }
 
-fn main() {
-    let s = include_str!("input.txt");
-    let result: NumberSum = serde_json::from_str(s).unwrap();
-    println!("Part1: {}", result.0);
-    let result: NumberSumRed = serde_json::from_str(s).unwrap();
-    println!("Part2: {}", result.0);
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = String;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        input.parse_with(|s| {
+            serde_json::from_str::<serde::de::IgnoredAny>(s).map_err(|e| Expected::json(s, &e))
+        })?;
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let result: NumberSum = serde_json::from_str(input)?;
+        Ok(result.0)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let result: NumberSumRed = serde_json::from_str(input)?;
+        Ok(result.0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::assert_matches::assert_matches;
+    use std::assert_matches;
 
     #[test]
     fn test_part1() {
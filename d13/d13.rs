@@ -1,4 +1,7 @@
-use aoc2015::inv_tsp;
+use crate::error::{Expected, number};
+use crate::input::PuzzleInput;
+use crate::inv_tsp;
+use crate::solution::Solution;
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -7,62 +10,89 @@ use std::str::FromStr;
 struct Instruction(String, i32, String);
 
 impl FromStr for Instruction {
-    type Err = ();
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        || -> Option<_> {
-            let mut words = s.split(" ");
-            let a = words.next()?.to_string();
-            words.next()?;
-            let amount = match words.next()? {
-                "gain" => 1,
-                "lose" => -1,
-                _ => return None,
-            } * words.next()?.parse::<i32>().ok()?;
-            (&mut words).take(6).last()?;
-            let b = words.next()?.split(".").next()?.to_string();
-            Some(Instruction(a, amount, b))
-        }()
-        .ok_or(())
+        let mut words = s.split(" ");
+        let mut next = |what: &str| words.next().ok_or_else(|| Expected::end(s, what));
+        let a = next("a name")?.to_string();
+        next("`would`")?;
+        let sign = match next("`gain` or `lose`")? {
+            "gain" => 1,
+            "lose" => -1,
+            word => return Err(Expected::at(s, word, "`gain` or `lose`")),
+        };
+        let amount = sign * number::<i32>(s, next("a number")?)?;
+        for what in [
+            "`happiness`",
+            "`units`",
+            "`by`",
+            "`sitting`",
+            "`next`",
+            "`to`",
+        ] {
+            next(what)?;
+        }
+        let b = next("a name")?.trim_end_matches('.').to_string();
+        Ok(Instruction(a, amount, b))
     }
 }
 
-fn main() {
-    // let input = include_str!("sample.txt");
-    let input = include_str!("input.txt");
-    let instructions: Vec<Instruction> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-    let names: Vec<_> = instructions
-        .iter()
-        .flat_map(|i| [i.0.as_str(), i.2.as_str()])
-        .unique()
-        .collect();
-    let name_to_index: HashMap<_, _> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
-    let n = names.len();
-    let mut matrix = vec![0; n * n];
-    let idx = |x: usize, y: usize| y * n + x;
-    for ins in &instructions {
-        let ia = name_to_index[ins.0.as_str()];
-        let ib = name_to_index[ins.2.as_str()];
-        matrix[idx(ia, ib)] = ins.1;
+/// How much happiness the person at the column index gains when sitting next to the person at
+/// the row index.
+pub struct Happiness {
+    n: usize,
+    matrix: Vec<i32>,
+}
+
+impl Happiness {
+    fn get(&self, x: usize, y: usize) -> i32 {
+        self.matrix[y * self.n + x]
     }
-    let best_happiness = inv_tsp(n as u16, |a, b| {
-        matrix[idx(a as usize, b as usize)] + matrix[idx(b as usize, a as usize)]
-    });
-    println!("Part1: {}", best_happiness);
+}
 
-    let best_happiness = inv_tsp((n + 1) as u16, |a, b| {
-        if a == 0 || b == 0 {
-            0
-        } else {
-            matrix[idx((a - 1) as usize, (b - 1) as usize)]
-                + matrix[idx((b - 1) as usize, (a - 1) as usize)]
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Happiness;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        let instructions: Vec<Instruction> = input.parse_lines()?;
+        let names: Vec<_> = instructions
+            .iter()
+            .flat_map(|i| [i.0.as_str(), i.2.as_str()])
+            .unique()
+            .collect();
+        let name_to_index: HashMap<_, _> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        let n = names.len();
+        let mut matrix = vec![0; n * n];
+        let idx = |x: usize, y: usize| y * n + x;
+        for ins in &instructions {
+            let ia = name_to_index[ins.0.as_str()];
+            let ib = name_to_index[ins.2.as_str()];
+            matrix[idx(ia, ib)] = ins.1;
         }
-    });
-    println!("Part2: {}", best_happiness);
+        Ok(Happiness { n, matrix })
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(inv_tsp(input.n as u16, |a, b| {
+            input.get(a as usize, b as usize) + input.get(b as usize, a as usize)
+        }))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(inv_tsp((input.n + 1) as u16, |a, b| {
+            if a == 0 || b == 0 {
+                0
+            } else {
+                input.get((a - 1) as usize, (b - 1) as usize)
+                    + input.get((b - 1) as usize, (a - 1) as usize)
+            }
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -79,5 +109,9 @@ mod tests {
             "Alice would lose 79 happiness units by sitting next to Carol.".parse(),
             Ok(Instruction("Alice".to_string(), -79, "Carol".to_string()))
         );
+        assert_eq!(
+            "Alice would win 79 happiness units by sitting next to Carol.".parse::<Instruction>(),
+            Err(Expected::new(12, "`gain` or `lose`"))
+        );
     }
 }
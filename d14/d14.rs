@@ -1,12 +1,14 @@
+use crate::from_str_via_nom;
+use crate::input::PuzzleInput;
+use crate::parse::{PResult, ident, token, uint};
+use crate::solution::Solution;
+use anyhow::Context;
 use nom::Parser;
-use nom::bytes::complete::tag;
-use nom::character::complete::{alphanumeric1, digit1};
-use nom::combinator::{eof, map, map_res};
+use nom::combinator::map;
 use std::cmp;
-use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-struct Reindeer {
+pub struct Reindeer {
     name: String,
     speed: i32,
     flight_seconds: i32,
@@ -27,35 +29,30 @@ struct ReindeerData {
     points: i32,
 }
 
-impl FromStr for Reindeer {
-    type Err = nom::Err<nom::error::Error<String>>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        map(
-            (
-                alphanumeric1,
-                tag(" can fly "),
-                map_res(digit1, str::parse),
-                tag(" km/s for "),
-                map_res(digit1, str::parse),
-                tag(" seconds, but then must rest for "),
-                map_res(digit1, str::parse),
-                tag(" seconds."),
-                eof,
-            ),
-            |(name, _, speed, _, flight_seconds, _, rest_seconds, _, _)| Reindeer {
-                name: name.to_string(),
-                speed,
-                flight_seconds,
-                rest_seconds,
-            },
-        )
-        .parse(s)
-        .map(|(_, r)| r)
-        .map_err(|e: nom::Err<nom::error::Error<_>>| e.to_owned())
-    }
+fn reindeer(i: &str) -> PResult<'_, Reindeer> {
+    map(
+        (
+            ident,
+            token(" can fly "),
+            uint,
+            token(" km/s for "),
+            uint,
+            token(" seconds, but then must rest for "),
+            uint,
+            token(" seconds."),
+        ),
+        |(name, _, speed, _, flight_seconds, _, rest_seconds, _)| Reindeer {
+            name: name.to_string(),
+            speed,
+            flight_seconds,
+            rest_seconds,
+        },
+    )
+    .parse(i)
 }
 
+from_str_via_nom!(Reindeer, reindeer);
+
 fn reindeer_distance(reindeer: &Reindeer, seconds: i32) -> i32 {
     let cycle_time = reindeer.flight_seconds + reindeer.rest_seconds;
     let full_cycles = seconds / cycle_time;
@@ -75,27 +72,11 @@ fn advance_reindeer(reindeer: &Reindeer, state: &ReindeerState) -> ReindeerState
     }
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let reindeer: Vec<Reindeer> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-
-    const SECONDS: i32 = 2503;
-
-    // part 1
-    let max_dist = reindeer
-        .iter()
-        .map(|r| reindeer_distance(r, SECONDS))
-        .max()
-        .unwrap();
-    println!("Part1: {}", max_dist);
-
-    // part 2
+/// Races the reindeer second by second, giving a point to the ones in the lead after each.
+fn simulate(reindeer: &[Reindeer], seconds: i32) -> Vec<ReindeerData> {
     let mut reindeer: Vec<_> = reindeer
-        .into_iter()
+        .iter()
+        .cloned()
         .map(|r| ReindeerData {
             reindeer: r,
             state: ReindeerState::Flying(0),
@@ -103,31 +84,59 @@ fn main() {
             points: 0i32,
         })
         .collect();
-    for _ in 0..SECONDS {
-        let max_dist = reindeer
-            .iter_mut()
-            .map(|data| {
-                if let ReindeerState::Flying(_) = data.state {
-                    data.distance_covered += data.reindeer.speed;
-                }
-                data.state = advance_reindeer(&data.reindeer, &data.state);
-                data.distance_covered
-            })
-            .max()
-            .unwrap();
-        reindeer.iter_mut().for_each(|data| {
-            if data.distance_covered == max_dist {
+    for _ in 0..seconds {
+        for data in &mut reindeer {
+            if let ReindeerState::Flying(_) = data.state {
+                data.distance_covered += data.reindeer.speed;
+            }
+            data.state = advance_reindeer(&data.reindeer, &data.state);
+        }
+        let max_dist = reindeer.iter().map(|data| data.distance_covered).max();
+        for data in &mut reindeer {
+            if Some(data.distance_covered) == max_dist {
                 data.points += 1;
             }
-        });
+        }
+    }
+    reindeer
+}
+
+const SECONDS: i32 = 2503;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Reindeer>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines()?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        input
+            .iter()
+            .map(|r| reindeer_distance(r, SECONDS))
+            .max()
+            .context("no reindeer")
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        simulate(input, SECONDS)
+            .iter()
+            .map(|data| data.points)
+            .max()
+            .context("no reindeer")
     }
-    let winner = reindeer.iter().map(|data| data.points).max().unwrap();
-    println!("Part2: {}", winner);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Expected;
+    use crate::generate::check_generated;
+    use anyhow::ensure;
 
     #[test]
     fn test_parse() {
@@ -140,6 +149,11 @@ mod tests {
                 rest_seconds: 127,
             })
         );
+        assert_eq!(
+            "Comet can fly 14 km/h for 10 seconds, but then must rest for 127 seconds."
+                .parse::<Reindeer>(),
+            Err(Expected::new(16, "` km/s for `"))
+        );
     }
 
     #[test]
@@ -174,4 +188,22 @@ mod tests {
         assert_eq!(advance_reindeer(&comet, &Resting(125)), Resting(126));
         assert_eq!(advance_reindeer(&comet, &Resting(126)), Flying(0));
     }
+
+    #[test]
+    fn test_closed_form_matches_simulation() {
+        check_generated(14, 5, 20, |input| {
+            let reindeer = Day14::parse(input)?;
+            for seconds in [0, 1, 10, 137, 1000, SECONDS] {
+                let simulated = simulate(&reindeer, seconds);
+                for (r, data) in reindeer.iter().zip(&simulated) {
+                    ensure!(
+                        reindeer_distance(r, seconds) == data.distance_covered,
+                        "{} after {seconds} seconds",
+                        r.name
+                    );
+                }
+            }
+            Ok(())
+        });
+    }
 }
@@ -1,17 +1,19 @@
-use aoc2015::compositions;
-use nom::IResult;
+use crate::compositions;
+use crate::from_str_via_nom;
+use crate::input::PuzzleInput;
+use crate::parse::{PResult, ident, int, token};
+use crate::solution::Solution;
+use anyhow::Context;
 use nom::Parser;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{alphanumeric1, char, space0, space1};
+use nom::character::complete::{space0, space1};
 use nom::combinator::{map, opt};
 use nom::multi::fold;
 use std::cmp;
 use std::iter::zip;
-use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
-struct Ingredient {
+pub struct Ingredient {
     name: String,
     capacity: i32,
     durability: i32,
@@ -20,67 +22,63 @@ struct Ingredient {
     calories: i32,
 }
 
-impl FromStr for Ingredient {
-    type Err = nom::Err<nom::error::Error<String>>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn properties(s: &str) -> IResult<&str, Ingredient> {
-            fold(
-                0..,
-                (
-                    alt((
-                        tag("capacity"),
-                        tag("durability"),
-                        tag("flavor"),
-                        tag("texture"),
-                        tag("calories"),
-                    )),
-                    space1,
-                    nom::character::complete::i32,
-                    opt(char(',')),
-                    space0,
-                ),
-                Ingredient::default,
-                |ingredient: Ingredient, (prop, _, v, ..)| match prop {
-                    "capacity" => Ingredient {
-                        capacity: v,
-                        ..ingredient
-                    },
-                    "durability" => Ingredient {
-                        durability: v,
-                        ..ingredient
-                    },
-                    "flavor" => Ingredient {
-                        flavor: v,
-                        ..ingredient
-                    },
-                    "texture" => Ingredient {
-                        texture: v,
-                        ..ingredient
-                    },
-                    "calories" => Ingredient {
-                        calories: v,
-                        ..ingredient
-                    },
-                    _ => ingredient,
+fn ingredient(i: &str) -> PResult<'_, Ingredient> {
+    fn properties(s: &str) -> PResult<'_, Ingredient> {
+        fold(
+            0..,
+            (
+                alt((
+                    token("capacity"),
+                    token("durability"),
+                    token("flavor"),
+                    token("texture"),
+                    token("calories"),
+                )),
+                space1,
+                int,
+                opt(token(",")),
+                space0,
+            ),
+            Ingredient::default,
+            |ingredient: Ingredient, (prop, _, v, ..)| match prop {
+                "capacity" => Ingredient {
+                    capacity: v,
+                    ..ingredient
                 },
-            )
-            .parse(s)
-        }
-
-        map(
-            (alphanumeric1, char(':'), space0, properties),
-            |(name, _, _, props)| Ingredient {
-                name: name.to_string(),
-                ..props
+                "durability" => Ingredient {
+                    durability: v,
+                    ..ingredient
+                },
+                "flavor" => Ingredient {
+                    flavor: v,
+                    ..ingredient
+                },
+                "texture" => Ingredient {
+                    texture: v,
+                    ..ingredient
+                },
+                "calories" => Ingredient {
+                    calories: v,
+                    ..ingredient
+                },
+                _ => ingredient,
             },
         )
         .parse(s)
-        .map(|(_, o)| o)
-        .map_err(<nom::Err<nom::error::Error<&str>>>::to_owned)
     }
+
+    map(
+        (ident, token(":"), space0, properties),
+        |(name, _, _, props)| Ingredient {
+            name: name.to_string(),
+            ..props
+        },
+    )
+    .parse(i)
 }
 
+from_str_via_nom!(Ingredient, ingredient);
+
 fn cookie_score(ingredients: &[Ingredient], amounts: &[u32]) -> i64 {
     let capacity: i32 = zip(ingredients, amounts)
         .map(|(i, &a)| i.capacity * a as i32)
@@ -107,26 +105,31 @@ fn cookie_calories(ingredients: &[Ingredient], amounts: &[u32]) -> i32 {
         .sum()
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let ingredients: Vec<Ingredient> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-
-    let best_score = compositions(ingredients.len() as u32, 100)
-        .map(|c| cookie_score(&ingredients, &c))
-        .max()
-        .unwrap();
-    println!("Part1: {}", best_score);
-
-    let best_score = compositions(ingredients.len() as u32, 100)
-        .filter(|c| cookie_calories(&ingredients, c) == 500)
-        .map(|c| cookie_score(&ingredients, &c))
-        .max()
-        .unwrap();
-    println!("Part2: {}", best_score);
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<Ingredient>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines()?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        compositions(input.len() as u32, 100)
+            .map(|c| cookie_score(input, &c))
+            .max()
+            .context("no ingredients")
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        compositions(input.len() as u32, 100)
+            .filter(|c| cookie_calories(input, c) == 500)
+            .map(|c| cookie_score(input, &c))
+            .max()
+            .context("no cookie has 500 calories")
+    }
 }
 
 #[cfg(test)]
@@ -1,61 +1,45 @@
+use crate::from_str_via_nom;
+use crate::input::PuzzleInput;
+use crate::parse::{PResult, ident, token, uint, ws};
+use crate::solution::Solution;
+use anyhow::anyhow;
 use itertools::Itertools;
+use lazy_static::lazy_static;
 use nom::Parser;
-use nom::bytes::tag;
-use nom::character::complete::{alphanumeric1, char, space0};
 use nom::combinator::map;
 use nom::multi::separated_list0;
 use std::collections::HashMap;
-use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
-struct Sue {
+pub struct Sue {
     number: u32,
     properties: HashMap<String, u32>,
 }
 
-impl FromStr for Sue {
-    type Err = nom::Err<nom::error::Error<String>>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        map(
-            (
-                tag("Sue"),
-                space0,
-                nom::character::complete::u32,
-                char(':'),
-                space0,
-                separated_list0(
-                    (char(','), space0),
-                    (
-                        alphanumeric1,
-                        char(':'),
-                        space0,
-                        nom::character::complete::u32,
-                    ),
-                ),
-            ),
-            |(_, _, number, _, _, props)| Sue {
-                number,
-                properties: props
-                    .into_iter()
-                    .map(|(name, _, _, v)| (str::to_string(name), v))
-                    .collect(),
-            },
-        )
-        .parse(s)
-        .map(|(_, o)| o)
-        .map_err(<nom::Err<nom::error::Error<&str>>>::to_owned)
-    }
+fn sue(i: &str) -> PResult<'_, Sue> {
+    map(
+        (
+            token("Sue"),
+            ws(uint),
+            token(":"),
+            separated_list0(token(","), (ws(ident), token(":"), ws(uint))),
+        ),
+        |(_, number, _, props)| Sue {
+            number,
+            properties: props
+                .into_iter()
+                .map(|(name, _, v)| (str::to_string(name), v))
+                .collect(),
+        },
+    )
+    .parse(i)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let sues: Vec<Sue> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-    let expected = HashMap::from([
+from_str_via_nom!(Sue, sue);
+
+lazy_static! {
+    /// What the MFCSAM found out about the Sue that sent the gift.
+    pub(crate) static ref EXPECTED: HashMap<&'static str, u32> = HashMap::from([
         ("children", 3),
         ("cats", 7),
         ("samoyeds", 2),
@@ -67,32 +51,48 @@ fn main() {
         ("cars", 2),
         ("perfumes", 1),
     ]);
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Vec<Sue>;
+    type Part1 = u32;
+    type Part2 = u32;
 
-    let sue = sues
-        .iter()
-        .filter(|sue| {
-            sue.properties
-                .iter()
-                .all(|(prop, &v)| expected[prop.as_str()] == v)
-        })
-        .exactly_one()
-        .unwrap();
-    println!("Part1: {}", sue.number);
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines()?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let sue = input
+            .iter()
+            .filter(|sue| {
+                sue.properties
+                    .iter()
+                    .all(|(prop, &v)| EXPECTED[prop.as_str()] == v)
+            })
+            .exactly_one()
+            .map_err(|e| anyhow!("expected exactly one matching Sue, got {}", e.count()))?;
+        Ok(sue.number)
+    }
 
-    let sue = sues
-        .iter()
-        .filter(|sue| {
-            sue.properties.iter().all(
-                |(prop, &v)| match (prop.as_str(), expected[prop.as_str()]) {
-                    ("cats" | "trees", expected) => v > expected,
-                    ("pomeranians" | "goldfish", expected) => v < expected,
-                    (_, expected) => v == expected,
-                },
-            )
-        })
-        .exactly_one()
-        .unwrap();
-    println!("Part2: {}", sue.number);
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let sue = input
+            .iter()
+            .filter(|sue| {
+                sue.properties.iter().all(|(prop, &v)| {
+                    match (prop.as_str(), EXPECTED[prop.as_str()]) {
+                        ("cats" | "trees", expected) => v > expected,
+                        ("pomeranians" | "goldfish", expected) => v < expected,
+                        (_, expected) => v == expected,
+                    }
+                })
+            })
+            .exactly_one()
+            .map_err(|e| anyhow!("expected exactly one matching Sue, got {}", e.count()))?;
+        Ok(sue.number)
+    }
 }
 
 #[cfg(test)]
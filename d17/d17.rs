@@ -1,31 +1,43 @@
-use std::str::FromStr;
-
-fn main() {
-    let containers = include_str!("input.txt")
-        .lines()
-        .map(i32::from_str)
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap();
-
-    let working_combinations = || {
-        (0..1u64 << containers.len()).filter(|&mask| {
-            (0..containers.len())
-                .map(|i| if 1 << i & mask != 0 { containers[i] } else { 0 })
-                .sum::<i32>()
-                == 150
-        })
-    };
-
-    let count = working_combinations().count();
-    println!("Part1: {}", count);
-
-    let min_amount = working_combinations()
-        .map(|mask| mask.count_ones())
-        .min()
-        .unwrap();
-
-    let count = working_combinations()
-        .filter(|mask| mask.count_ones() == min_amount)
-        .count();
-    println!("Part2: {}", count);
+use crate::error::number;
+use crate::input::PuzzleInput;
+use crate::solution::Solution;
+use anyhow::Context;
+
+const LITERS: i32 = 150;
+
+/// All subsets of containers, as bitmasks, that hold exactly [LITERS] liters.
+fn working_combinations(containers: &[i32]) -> impl Iterator<Item = u64> {
+    (0..1u64 << containers.len()).filter(|&mask| {
+        (0..containers.len())
+            .map(|i| if 1 << i & mask != 0 { containers[i] } else { 0 })
+            .sum::<i32>()
+            == LITERS
+    })
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<i32>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines_with(|line| number(line, line.trim()))?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(working_combinations(input).count())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let min_amount = working_combinations(input)
+            .map(|mask| mask.count_ones())
+            .min()
+            .context("no combination of containers works")?;
+
+        Ok(working_combinations(input)
+            .filter(|mask| mask.count_ones() == min_amount)
+            .count())
+    }
 }
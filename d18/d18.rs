@@ -1,151 +1,178 @@
-use std::str::FromStr;
+use crate::grid::{Grid, Pos};
+use crate::input::PuzzleInput;
+use crate::repl::{Command, Explore, arg_or};
+use crate::solution::Solution;
 
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
-struct Grid {
-    data: Vec<bool>,
-    width: i32,
-    height: i32,
-}
+/// The lights, `true` where they are on.
+pub type Lights = Grid<bool>;
 
-impl FromStr for Grid {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut grid = Grid::default();
-        for (i, line) in s.lines().filter(|l| !l.trim().is_empty()).enumerate() {
-            let line = line.trim();
-            if i == 0 {
-                grid.width = line.len() as i32;
-            } else if grid.width != line.len() as i32 {
-                return Err("line lengths not matching".to_string());
-            }
-            grid.height = i as i32 + 1;
-            grid.data.reserve(line.len());
-            for c in line.chars() {
-                grid.data.push(match c {
-                    '#' => true,
-                    '.' => false,
-                    _ => return Err(format!("Expected '#' or '.', got {c}")),
-                });
-            }
-        }
-        Ok(grid)
-    }
+fn neighbor_count(grid: &Lights, pos: Pos) -> usize {
+    grid.neighbors_8(pos).filter(|&p| grid[p]).count()
 }
 
-impl Grid {
-    fn idx(&self, x: i32, y: i32) -> usize {
-        y as usize * self.width as usize + x as usize
-    }
+fn conways_game_of_life(grid: Lights) -> Lights {
+    Grid::from_fn(grid.width(), grid.height(), |pos| {
+        matches!(
+            (grid[pos], neighbor_count(&grid, pos)),
+            (true, 2 | 3) | (false, 3)
+        )
+    })
+}
 
-    fn get(&self, x: i32, y: i32) -> bool {
-        if x < 0 || x >= self.width || y < 0 || y >= self.height {
-            false
-        } else {
-            self.data[self.idx(x, y)]
+fn turn_corners_on(mut grid: Lights) -> Lights {
+    if grid.width() == 0 || grid.height() == 0 {
+        grid
+    } else {
+        let (right, bottom) = (grid.width() - 1, grid.height() - 1);
+        for corner in [[0, 0], [right, 0], [0, bottom], [right, bottom]] {
+            grid[corner] = true;
         }
+        grid
     }
+}
 
-    fn set(&mut self, x: i32, y: i32, v: bool) {
-        if x < 0 || x >= self.width || y < 0 || y > self.height {
-            panic!("x or y out of bounds!");
-        }
-        let i = self.idx(x, y);
-        self.data[i] = v
+/// The lights as the REPL steps them, and how many steps that took.
+pub struct Life {
+    start: Lights,
+    lights: Lights,
+    steps: usize,
+    /// Whether the corners are stuck on, as in part 2.
+    stuck: bool,
+}
+
+impl Life {
+    fn status(&self) -> String {
+        format!(
+            "{} lights on after {} steps",
+            self.lights.iter().filter(|b| **b).count(),
+            self.steps
+        )
     }
 }
 
-fn neighbor_count(grid: &Grid, x: i32, y: i32) -> i32 {
-    (-1..=1)
-        .flat_map(|y_off| {
-            (-1..=1)
-                .map(move |x_off| (x_off, y_off))
-                .filter(|(x_off, y_off)| *x_off != 0 || *y_off != 0)
+impl Explore for Life {
+    const COMMANDS: &'static [Command<Self>] = &[
+        Command {
+            name: "step",
+            args: "[COUNT]",
+            help: "Runs the game of life for one or more steps.",
+            run: |life, args| {
+                for _ in 0..arg_or(args, 0, "step count", 1)? {
+                    life.lights = conways_game_of_life(life.lights.clone());
+                    if life.stuck {
+                        life.lights = turn_corners_on(life.lights.clone());
+                    }
+                    life.steps += 1;
+                }
+                Ok(life.status())
+            },
+        },
+        Command {
+            name: "show",
+            args: "",
+            help: "Draws the lights.",
+            run: |life, _| Ok(format!("{}\n{}", life.lights, life.status())),
+        },
+        Command {
+            name: "stuck",
+            args: "",
+            help: "Sticks the corners on from now on, or unsticks them.",
+            run: |life, _| {
+                life.stuck = !life.stuck;
+                if life.stuck {
+                    life.lights = turn_corners_on(life.lights.clone());
+                }
+                Ok(format!("corners stuck: {}", life.stuck))
+            },
+        },
+        Command {
+            name: "reset",
+            args: "",
+            help: "Goes back to the lights from the input.",
+            run: |life, _| {
+                life.lights = life.start.clone();
+                life.steps = 0;
+                life.stuck = false;
+                Ok(life.status())
+            },
+        },
+    ];
+
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self> {
+        let lights = Day18::parse(input)?;
+        Ok(Life {
+            start: lights.clone(),
+            lights,
+            steps: 0,
+            stuck: false,
         })
-        .filter(|(x_off, y_off)| grid.get(x + x_off, y + y_off))
-        .count() as i32
+    }
 }
 
-fn conways_game_of_life(grid: Grid) -> Grid {
-    let mut result = grid.clone();
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            result.set(
-                x,
-                y,
-                matches!(
-                    (grid.get(x, y), neighbor_count(&grid, x, y)),
-                    (true, 2 | 3) | (false, 3)
-                ),
-            );
-        }
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Lights;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_with(str::parse)?)
     }
-    result
-}
 
-fn turn_corners_on(mut grid: Grid) -> Grid {
-    if grid.width == 0 || grid.height == 0 {
-        grid
-    } else {
-        grid.set(0, 0, true);
-        grid.set(grid.width - 1, 0, true);
-        grid.set(0, grid.height - 1, true);
-        grid.set(grid.width - 1, grid.height - 1, true);
-        grid
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let result = (0..100).fold(input.clone(), |g, _| conways_game_of_life(g));
+        Ok(result.iter().filter(|b| **b).count())
     }
-}
 
-fn main() {
-    let grid: Grid = include_str!("input.txt").parse().unwrap();
-    let result = (0..100).fold(grid.clone(), |g, _| conways_game_of_life(g));
-    let count = result.data.iter().filter(|b| **b).count();
-    println!("Part1: {}", count);
-    let grid = turn_corners_on(grid);
-    let result = (0..100).fold(grid, |g, _| turn_corners_on(conways_game_of_life(g)));
-    let count = result.data.iter().filter(|b| **b).count();
-    println!("Part2: {}", count);
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let grid = turn_corners_on(input.clone());
+        let result = (0..100).fold(grid, |g, _| turn_corners_on(conways_game_of_life(g)));
+        Ok(result.iter().filter(|b| **b).count())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Expected;
 
     #[test]
     fn test_parse() {
         assert_eq!(
             "#.#\n.#.\n..#".parse(),
-            Ok(Grid {
-                data: vec![true, false, true, false, true, false, false, false, true],
-                width: 3,
-                height: 3,
-            })
-        )
+            Ok(Grid::from_vec(
+                3,
+                3,
+                vec![true, false, true, false, true, false, false, false, true]
+            ))
+        );
+        assert_eq!(
+            "#.#\n.x.\n..#".parse::<Lights>(),
+            Err(Expected::new(5, "`#` or `.`"))
+        );
     }
 
     #[test]
     fn test_neighbor_count() {
-        let grid = Grid {
-            data: vec![true],
-            width: 1,
-            height: 1,
-        };
-        assert_eq!(neighbor_count(&grid, 0, 0), 0);
-
-        let grid = Grid {
-            data: vec![false, false, false, false, true, false, false, false, false],
-            width: 3,
-            height: 3,
-        };
-        assert_eq!(neighbor_count(&grid, 1, 1), 0);
-        assert_eq!(neighbor_count(&grid, 0, 0), 1);
-
-        let grid = Grid {
-            data: vec![true, false, false, true, true, false, false, true, false],
-            width: 3,
-            height: 3,
-        };
-        assert_eq!(neighbor_count(&grid, 1, 1), 3);
-        assert_eq!(neighbor_count(&grid, 0, 0), 2);
+        let grid = Grid::from_vec(1, 1, vec![true]);
+        assert_eq!(neighbor_count(&grid, [0, 0]), 0);
+
+        let grid = Grid::from_vec(
+            3,
+            3,
+            vec![false, false, false, false, true, false, false, false, false],
+        );
+        assert_eq!(neighbor_count(&grid, [1, 1]), 0);
+        assert_eq!(neighbor_count(&grid, [0, 0]), 1);
+
+        let grid = Grid::from_vec(
+            3,
+            3,
+            vec![true, false, false, true, true, false, false, true, false],
+        );
+        assert_eq!(neighbor_count(&grid, [1, 1]), 3);
+        assert_eq!(neighbor_count(&grid, [0, 0]), 2);
     }
 
     #[test]
@@ -193,4 +220,25 @@ mod tests {
             .unwrap();
         assert_eq!((0..4).fold(a, |g, _| conways_game_of_life(g)), b);
     }
+
+    #[test]
+    fn test_life() {
+        use crate::repl::Session;
+        let input = PuzzleInput::new(".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..\n", "");
+        let mut life = Life::load(&input).unwrap();
+        let mut call = |line: &str| {
+            let words: Vec<_> = line.split(' ').collect();
+            life.call(words[0], &words[1..]).unwrap().unwrap()
+        };
+        assert_eq!(call("step 4"), "4 lights on after 4 steps");
+        assert_eq!(
+            call("show"),
+            "......\n......\n..##..\n..##..\n......\n......\n\n4 lights on after 4 steps"
+        );
+        assert_eq!(call("reset"), "15 lights on after 0 steps");
+        assert_eq!(call("stuck"), "corners stuck: true");
+        assert_eq!(call("step 5"), "17 lights on after 5 steps");
+        assert_eq!(call("reset"), "15 lights on after 0 steps");
+        assert_eq!(call("step"), "11 lights on after 1 steps");
+    }
 }
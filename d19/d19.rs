@@ -1,15 +1,21 @@
-use aoc2015::graph::a_star_rev;
+use crate::error::Expected;
+use crate::from_str_via_nom;
+use crate::graph::{
+    OptimalPaths, a_star_rev, all_optimal_paths, bidirectional_rev, ida_star_rev_bounded,
+};
+use crate::input::PuzzleInput;
+use crate::parse::{PResult, letters, token};
+use crate::repl::{Command, Explore, arg_or};
+use crate::solution::Solution;
 use nom::Parser;
-use nom::bytes::complete::tag;
-use nom::character::complete::alpha1;
-use nom::combinator::{eof, map};
+use nom::combinator::map;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-struct Replacement {
+pub struct Replacement {
     from: String,
     to: String,
 }
@@ -21,25 +27,19 @@ impl fmt::Display for Replacement {
     }
 }
 
-impl FromStr for Replacement {
-    type Err = nom::Err<nom::error::Error<String>>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        map((alpha1, tag(" => "), alpha1, eof), |(from, _, to, ..)| {
-            Replacement {
-                from: str::to_string(from),
-                to: str::to_string(to),
-            }
-        })
-        .parse(s)
-        .map(|(_, r)| r)
-        .map_err(<nom::Err<nom::error::Error<&str>>>::to_owned)
-    }
+fn replacement(i: &str) -> PResult<'_, Replacement> {
+    map((letters, token(" => "), letters), |(from, _, to)| {
+        Replacement {
+            from: str::to_string(from),
+            to: str::to_string(to),
+        }
+    })
+    .parse(i)
 }
 
-fn parse_input(
-    input: &str,
-) -> Result<(Vec<Replacement>, String), nom::Err<nom::error::Error<String>>> {
+from_str_via_nom!(Replacement, replacement);
+
+fn parse_input(input: &str) -> Result<(Vec<Replacement>, String), Expected> {
     enum State {
         Replacements,
         Molecule,
@@ -52,16 +52,21 @@ fn parse_input(
         } else if let State::Molecule = state {
             return Ok((vec, line.to_string()));
         } else {
-            vec.push(line.parse()?);
+            vec.push(line.parse().map_err(|e: Expected| e.within(input, line))?);
         }
     }
-    Err(nom::Err::Incomplete(nom::Needed::Unknown))
+    Err(Expected::end(
+        input,
+        "an empty line followed by the molecule",
+    ))
 }
 
+/// Makes every replacement wherever it can be made, overlapping matches included, like
+/// [apply_replacements_reverse_verbose] undoes them.
 fn apply_replacements(molecule: &str, replacements: &[Replacement]) -> HashSet<String> {
     let mut result = HashSet::new();
     for replacement in replacements {
-        for (index, _) in molecule.match_indices(&replacement.from) {
+        for index in (0..molecule.len()).filter(|&i| molecule[i..].starts_with(&replacement.from)) {
             let mut s = molecule.to_string();
             s.replace_range(index..index + replacement.from.len(), &replacement.to);
             result.insert(s);
@@ -70,13 +75,32 @@ fn apply_replacements(molecule: &str, replacements: &[Replacement]) -> HashSet<S
     result
 }
 
+/// Makes every replacement wherever it can be made, like [apply_replacements], but also says
+/// where and which, in the same way as [apply_replacements_reverse_verbose].
+fn apply_replacements_verbose<'r>(
+    molecule: &str,
+    replacements: &'r [Replacement],
+) -> Vec<(String, usize, &'r Replacement)> {
+    let mut result = Vec::new();
+    for replacement in replacements {
+        for index in (0..molecule.len()).filter(|&i| molecule[i..].starts_with(&replacement.from)) {
+            let mut s = molecule.to_string();
+            s.replace_range(index..index + replacement.from.len(), &replacement.to);
+            result.push((s, index, replacement));
+        }
+    }
+    result
+}
+
+/// Undoes every replacement wherever it could have been made. Unlike [str::match_indices], this
+/// also finds overlapping matches, like both `TiTi`s in `TiTiTi`.
 fn apply_replacements_reverse_verbose<'r>(
     molecule: &str,
     replacements: &'r [Replacement],
 ) -> Vec<(String, usize, &'r Replacement)> {
     let mut result = Vec::new();
     for replacement in replacements {
-        for (index, _) in molecule.match_indices(&replacement.to) {
+        for index in (0..molecule.len()).filter(|&i| molecule[i..].starts_with(&replacement.to)) {
             let mut s = molecule.to_string();
             s.replace_range(index..index + replacement.to.len(), &replacement.from);
             result.push((s, index, replacement));
@@ -92,15 +116,20 @@ fn synthesize<'r>(
 ) -> Result<Vec<(String, usize, &'r Replacement)>, String> {
     let h = |a: &str| strsim::levenshtein(a, start) as i64;
 
-    let mut open_set = HashSet::from([molecule.to_string()]);
+    // Each open molecule remembers when it was last added, so that ties between equal f_scores
+    // are broken the same way as in [a_star_rev], no matter how the map is ordered.
+    let mut pushes = 0u64;
+    let mut open_set = HashMap::from([(molecule.to_string(), pushes)]);
     let mut came_from = HashMap::<_, (String, usize, &'r Replacement)>::new();
     let mut g_score = HashMap::from([(molecule.to_string(), 0i64)]);
     let mut f_score = HashMap::from([(molecule.to_string(), h(molecule))]);
 
-    while let Some(current) = open_set
-        .iter()
-        .min_by_key(|s| f_score.get(s.as_str()).copied().unwrap_or(i64::MAX))
-    {
+    while let Some((current, _)) = open_set.iter().min_by_key(|&(s, pushed)| {
+        (
+            f_score.get(s.as_str()).copied().unwrap_or(i64::MAX),
+            *pushed,
+        )
+    }) {
         if current == start {
             let mut total_path = Vec::new();
             let mut current = current;
@@ -123,7 +152,8 @@ fn synthesize<'r>(
                 came_from.insert(neighbor.clone(), (current.clone(), index, replacement));
                 g_score.insert(neighbor.clone(), tentative_g_score);
                 f_score.insert(neighbor.clone(), tentative_g_score + h(&neighbor));
-                open_set.insert(neighbor);
+                pushes += 1;
+                open_set.insert(neighbor, pushes);
             }
         }
     }
@@ -131,12 +161,8 @@ fn synthesize<'r>(
     Err("Didn't work".to_string())
 }
 
-/// We can't check for equality with [synthesize], because the result seems to be different every
-/// time even within the same function. I'm guessing this because of the iteration over [HashSet],
-/// and [Iterator::min_by_key]. If there are multiple elements with the same `f_score`,
-/// undeterministic behavior of the set's allocation would result in different paths chosen.
-/// However, the length of the path seems to be consistent, meaning that the algorithm correctly
-/// returns one of the equally optimal solutions.
+/// The same search as [synthesize], but with [a_star_rev]. Both break ties between equal
+/// f_scores by the order the molecules were added in, so they find the very same path.
 #[allow(dead_code)]
 fn synthesize_2<'r>(
     molecule: &str,
@@ -158,26 +184,213 @@ fn synthesize_2<'r>(
     .map_err(|e| e.to_string())
 }
 
-fn main() {
-    let (replacements, molecule) = parse_input(include_str!("input.txt")).unwrap();
+/// Another way to [synthesize], which undoes replacements from the molecule and makes them from
+/// `start` at the same time until the two meet. It always finds the fewest steps, but making
+/// replacements branches a lot, so it only works for short molecules.
+fn synthesize_bidirectional<'r>(
+    molecule: &str,
+    start: &str,
+    replacements: &'r [Replacement],
+) -> Result<Vec<(String, usize, &'r Replacement)>, String> {
+    let neighbors = |reverse: bool| {
+        move |current: &String| {
+            let result = if reverse {
+                apply_replacements_reverse_verbose(current, replacements)
+            } else {
+                apply_replacements_verbose(current, replacements)
+            };
+            result.into_iter().map(|(m, i, r)| (m, (i, r)))
+        }
+    };
+    bidirectional_rev(
+        &molecule.to_string(),
+        &start.to_string(),
+        neighbors(true),
+        neighbors(false),
+        |_, _, _| 1,
+    )
+    .map(|path| path.into_iter().map(|(m, (i, r))| (m, i, r)).collect())
+    .map_err(|e| e.to_string())
+}
+
+/// The same search as [synthesize_2], but with [ida_star_rev_bounded], so that it remembers at
+/// most `max_nodes` molecules at a time.
+fn synthesize_bounded<'r>(
+    molecule: &str,
+    start: &str,
+    replacements: &'r [Replacement],
+    max_nodes: usize,
+) -> Result<Vec<(String, usize, &'r Replacement)>, String> {
+    ida_star_rev_bounded(
+        &molecule.to_string(),
+        |n| n == start,
+        |current| {
+            apply_replacements_reverse_verbose(current, replacements)
+                .into_iter()
+                .map(|(m, i, r)| (m, (i, r)))
+        },
+        |a| strsim::levenshtein(a, start) as i64,
+        |_, _, _| 1,
+        max_nodes,
+    )
+    .map(|path| path.0.into_iter().map(|(m, (i, r))| (m, i, r)).collect())
+    .map_err(|e| e.to_string())
+}
+
+/// Every way to make the molecule from `start` in as few steps as possible. There are far too many
+/// for the real input, but they can be counted for short molecules.
+fn minimal_derivations<'r>(
+    molecule: &str,
+    start: &str,
+    replacements: &'r [Replacement],
+) -> Result<OptimalPaths<String, (usize, &'r Replacement)>, String> {
+    all_optimal_paths(
+        &molecule.to_string(),
+        |n| n == start,
+        |current| {
+            apply_replacements_reverse_verbose(current, replacements)
+                .into_iter()
+                .map(|(m, i, r)| (m, (i, r)))
+        },
+        |_| 0,
+        |_, _, _| 1,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// How to find the way from `e` to the molecule in the REPL.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Strategy {
+    /// [synthesize], which part 2 uses.
+    AStar,
+    /// [synthesize_bidirectional].
+    Bidirectional,
+    /// [synthesize_bounded].
+    Bounded,
+}
+
+impl FromStr for Strategy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a-star" => Ok(Strategy::AStar),
+            "bidirectional" => Ok(Strategy::Bidirectional),
+            "bounded" => Ok(Strategy::Bounded),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The replacements and the molecule, for trying the ways to synthesize it in the REPL.
+pub struct Chemistry {
+    replacements: Vec<Replacement>,
+    molecule: String,
+}
+
+impl Explore for Chemistry {
+    const COMMANDS: &'static [Command<Self>] = &[
+        Command {
+            name: "synthesize",
+            args: "[a-star|bidirectional|bounded [MAX_NODES]]",
+            help: "Makes the molecule from e, with A* like part 2, from both ends for short \
+               molecules, or with IDA* remembering at most MAX_NODES molecules.",
+            run: |c, args| {
+                let strategy = arg_or(args, 0, "strategy", Strategy::AStar)?;
+                let (molecule, replacements) = (&c.molecule, &c.replacements);
+                let steps = match strategy {
+                    Strategy::AStar => synthesize(molecule, "e", replacements),
+                    Strategy::Bidirectional => {
+                        synthesize_bidirectional(molecule, "e", replacements)
+                    }
+                    Strategy::Bounded => {
+                        let max_nodes = arg_or(args, 1, "max nodes", 10_000)?;
+                        synthesize_bounded(molecule, "e", replacements, max_nodes)
+                    }
+                }
+                .map_err(anyhow::Error::msg)?;
+                let mut lines = vec![format!("{} steps", steps.len())];
+                lines.extend(
+                    steps
+                        .iter()
+                        .map(|(s, i, r)| format!("{r: <16} at {i: >3} | {s}")),
+                );
+                Ok(lines.join("\n"))
+            },
+        },
+        Command {
+            name: "derivations",
+            args: "[COUNT] [MOLECULE]",
+            help: "Counts the shortest ways to make a short molecule from e, and lists up to \
+                   COUNT of them.",
+            run: |c, args| {
+                let count = arg_or(args, 0, "count", 10)?;
+                let molecule = args.get(1).copied().unwrap_or(&c.molecule);
+                let derivations = minimal_derivations(molecule, "e", &c.replacements)
+                    .map_err(anyhow::Error::msg)?;
+                let ways = match derivations.count() {
+                    1 => "1 way".to_string(),
+                    n => format!("{n} ways"),
+                };
+                let mut lines = vec![format!("{ways} in {} steps", derivations.cost())];
+                for (path_rev, _) in derivations.paths_rev().take(count) {
+                    let steps: Vec<_> = path_rev
+                        .iter()
+                        .map(|(_, (i, r))| format!("{r} at {i}"))
+                        .collect();
+                    lines.push(steps.join(", "));
+                }
+                Ok(lines.join("\n"))
+            },
+        },
+    ];
+
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self> {
+        let (replacements, molecule) = Day19::parse(input)?;
+        Ok(Chemistry {
+            replacements,
+            molecule,
+        })
+    }
+}
+
+pub struct Day19;
 
-    let result = apply_replacements(&molecule, &replacements);
-    println!("Part1: {}", result.len());
+impl Solution for Day19 {
+    type Input = (Vec<Replacement>, String);
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let result = synthesize(&molecule, "e", &replacements).unwrap();
-    println!("Part2: {}", result.len());
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_with(parse_input)?)
+    }
 
-    // verbose printout because it's very cool:
-    println!("Replacements to get from 'e' to '{}':", molecule);
-    println!("{: <23} | e", "BEGIN");
-    for (s, i, r) in result {
-        println!("{: <16} at {: >3} | {}", r, i, s);
+    fn part1((replacements, molecule): &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(apply_replacements(molecule, replacements).len())
+    }
+
+    fn part2((replacements, molecule): &Self::Input) -> anyhow::Result<Self::Part2> {
+        let result = synthesize(molecule, "e", replacements).map_err(anyhow::Error::msg)?;
+
+        // verbose printout because it's very cool:
+        if log::log_enabled!(log::Level::Debug) {
+            log::debug!("Replacements to get from 'e' to '{}':", molecule);
+            log::debug!("{: <23} | e", "BEGIN");
+            for (s, i, r) in &result {
+                log::debug!("{: <16} at {: >3} | {}", r, i, s);
+            }
+        }
+
+        Ok(result.len())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::check_generated;
+    use crate::graph::{HeuristicError, check_heuristic};
+    use anyhow::ensure;
 
     #[test]
     fn test_parse() {
@@ -188,6 +401,10 @@ mod tests {
                 to: "ThF".to_string()
             })
         );
+        assert_eq!(
+            "Al -> ThF".parse::<Replacement>(),
+            Err(Expected::new(2, "` => `"))
+        );
     }
 
     #[test]
@@ -222,6 +439,30 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_apply_replacements_overlapping() {
+        let replacements = [Replacement {
+            from: "TiTi".to_string(),
+            to: "X".to_string(),
+        }];
+        let expected = HashSet::from(["XTi", "TiX"].map(str::to_string));
+        assert_eq!(apply_replacements("TiTiTi", &replacements), expected);
+    }
+
+    #[test]
+    fn test_apply_replacements_reverse_overlapping() {
+        let replacements = [Replacement {
+            from: "X".to_string(),
+            to: "TiTi".to_string(),
+        }];
+        let actual = apply_replacements_reverse_verbose("TiTiTi", &replacements)
+            .into_iter()
+            .map(|(s, i, _)| (s, i))
+            .collect::<Vec<_>>();
+        let expected = vec![("XTi".to_string(), 0), ("TiX".to_string(), 2)];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_synthesize_a() {
         let (replacements, input) =
@@ -241,8 +482,173 @@ mod tests {
         let result1 = synthesize(&input, "e", &replacements).unwrap();
         let result2 = synthesize_2(&input, "e", &replacements).unwrap();
         assert_eq!(result1.len(), 6);
-        assert_eq!(result2.len(), 6);
         assert_eq!(result1.last().unwrap().0, "HOHOHO");
-        assert_eq!(result2.last().unwrap().0, "HOHOHO");
+        assert_eq!(result1, result2);
+        assert_eq!(synthesize(&input, "e", &replacements).unwrap(), result1);
+        let result3 = synthesize_bidirectional(&input, "e", &replacements).unwrap();
+        assert_eq!(result3.len(), 6);
+        assert_eq!(result3.last().unwrap().0, "HOHOHO");
+    }
+
+    #[test]
+    fn test_synthesize_bidirectional() {
+        let (replacements, molecule) =
+            parse_input("e => H\ne => O\nH => HO\nH => OH\nO => HH\nH => HOHOH\n\nHOHOH\n")
+                .unwrap();
+        let path = synthesize_bidirectional(&molecule, "e", &replacements).unwrap();
+        // e => H => HOHOH
+        assert_eq!(path.len(), 2);
+        assert_eq!(replay("e", &path).unwrap(), molecule);
+    }
+
+    #[test]
+    fn test_synthesize_bounded() {
+        let (replacements, input) =
+            parse_input("e => H\ne => O\nH => HO\nH => OH\nO => HH\n\nHOHOHO\n").unwrap();
+        for max_nodes in [0, 10, 1000] {
+            let path = synthesize_bounded(&input, "e", &replacements, max_nodes).unwrap();
+            assert_eq!(path.len(), 6);
+            assert_eq!(replay("e", &path).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_minimal_derivations() {
+        fn count(from: &str, to: &str, steps: usize, replacements: &[Replacement]) -> u64 {
+            match steps {
+                0 => (from == to) as u64,
+                _ => apply_replacements_verbose(from, replacements)
+                    .iter()
+                    .map(|(next, _, _)| count(next, to, steps - 1, replacements))
+                    .sum(),
+            }
+        }
+        let (replacements, input) =
+            parse_input("e => H\ne => O\nH => HO\nH => OH\nO => HH\n\nHOHOHO\n").unwrap();
+        let derivations = minimal_derivations(&input, "e", &replacements).unwrap();
+        assert_eq!(derivations.cost(), 6);
+        assert_eq!(derivations.count(), count("e", &input, 6, &replacements));
+        assert!(derivations.count() > 1);
+        for (path_rev, _) in derivations.paths_rev() {
+            let path: Vec<_> = path_rev.into_iter().map(|(m, (i, r))| (m, i, r)).collect();
+            assert_eq!(replay("e", &path).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_chemistry() {
+        use crate::repl::Session;
+        let input = PuzzleInput::new("e => H\ne => O\nH => HO\nH => OH\nO => HH\n\nHOH\n", "");
+        let mut chemistry = Chemistry::load(&input).unwrap();
+        let mut call = |line: &str| {
+            let words: Vec<_> = line.split(' ').collect();
+            chemistry.call(words[0], &words[1..]).unwrap()
+        };
+        let output = call("synthesize").unwrap();
+        assert!(output.starts_with("3 steps\n"));
+        assert!(output.ends_with("| HOH"));
+        for line in [
+            "synthesize bidirectional",
+            "synthesize bounded",
+            "synthesize bounded 0",
+        ] {
+            assert!(call(line).unwrap().starts_with("3 steps\n"), "{line}");
+        }
+        assert_eq!(
+            call("synthesize sideways").unwrap_err().to_string(),
+            "invalid strategy `sideways`"
+        );
+        assert_eq!(
+            call("derivations 1 HO").unwrap(),
+            "1 way in 2 steps\ne => H at 0, H => HO at 0"
+        );
+        assert_eq!(
+            call("derivations").unwrap(),
+            "2 ways in 3 steps\n\
+             e => O at 0, O => HH at 0, H => HO at 0\n\
+             e => O at 0, O => HH at 0, H => OH at 1"
+        );
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let check = |input: &str| {
+            let (replacements, molecule) = parse_input(input).unwrap();
+            check_heuristic(
+                &molecule,
+                |n| n == "e",
+                |current| {
+                    apply_replacements_reverse_verbose(current, &replacements)
+                        .into_iter()
+                        .map(|(m, i, r)| (m, (i, r.to_string())))
+                },
+                |a| strsim::levenshtein(a, "e") as i64,
+                |_, _, _| 1,
+                10_000,
+            )
+        };
+        // Every replacement makes the molecule one longer, so the heuristic is right.
+        assert_eq!(
+            check("e => H\ne => O\nH => HO\nH => OH\nO => HH\n\nHOHOHO\n"),
+            vec![]
+        );
+        // But not when one adds more than that.
+        let errors = check("e => H\nH => HOHOH\n\nHOHOH\n");
+        assert_eq!(
+            errors[0],
+            HeuristicError::Overestimate {
+                node: "HOHOH".to_string(),
+                h: 5,
+                actual: 2
+            }
+        );
+    }
+
+    /// Makes the replacements of a path from [synthesize], checking each molecule on the way.
+    fn replay(start: &str, path: &[(String, usize, &Replacement)]) -> anyhow::Result<String> {
+        let mut current = start.to_string();
+        for (s, i, r) in path {
+            ensure!(
+                current.get(*i..*i + r.from.len()) == Some(&r.from),
+                "no {r} at {i}"
+            );
+            current.replace_range(*i..*i + r.from.len(), &r.to);
+            ensure!(&current == s, "{current} instead of {s}");
+        }
+        Ok(current)
+    }
+
+    #[test]
+    fn test_synthesize_variants_agree() {
+        check_generated(19, 8, 20, |input| {
+            let (replacements, molecule) = Day19::parse(input)?;
+            let result1 = synthesize(&molecule, "e", &replacements).map_err(anyhow::Error::msg)?;
+            let result2 =
+                synthesize_2(&molecule, "e", &replacements).map_err(anyhow::Error::msg)?;
+            ensure!(
+                result1 == result2,
+                "{} steps with synthesize, {} with synthesize_2",
+                result1.len(),
+                result2.len()
+            );
+            let result3 = synthesize_bidirectional(&molecule, "e", &replacements)
+                .map_err(anyhow::Error::msg)?;
+            ensure!(
+                result3.len() <= result1.len(),
+                "{} steps with synthesize_bidirectional, {} with synthesize",
+                result3.len(),
+                result1.len()
+            );
+            let result4 = synthesize_bounded(&molecule, "e", &replacements, 100)
+                .map_err(anyhow::Error::msg)?;
+            ensure!(replay("e", &result4)? == molecule);
+            ensure!(
+                result4.len() >= result3.len(),
+                "{} steps with synthesize_bounded, {} with synthesize_bidirectional",
+                result4.len(),
+                result3.len()
+            );
+            Ok(())
+        });
     }
 }
@@ -1,4 +1,8 @@
-use indicatif::ProgressBar;
+use crate::error::number;
+use crate::input::PuzzleInput;
+use crate::progress;
+use crate::progress::Progress;
+use crate::solution::Solution;
 
 fn present_count(house: u64) -> u64 {
     (1..=house)
@@ -14,62 +18,75 @@ fn present_count_2(house: u64) -> u64 {
         .sum()
 }
 
-// fn main() {
-//     let input = 36000i64;
-//     let mut min_house = u64::MAX;
-//     for composition in partial_compositions(input / 10, input / 10) {
-//         let house = composition.into_iter().map(|c| c as u64).product();
-//         min_house = cmp::min(min_house, house);
-//     }
-//     println!(
-//         "Min House: {} with {} presents",
-//         min_house,
-//         present_count(min_house)
-//     );
-// }
+/// Like [lowest_house], but lets every elf deliver to its houses in turn instead of asking every
+/// house for its elves. Each elf brings `per_elf` presents for every number it is, and stops after
+/// `visits` houses if there is a limit. No house beyond `input / per_elf` needs to be looked at,
+/// because that one gets enough from its own elf alone. Only the tests use it, to check
+/// [lowest_house] against.
+#[cfg(test)]
+fn lowest_house_sieve(input: u64, per_elf: u64, visits: Option<u64>) -> u64 {
+    let limit = input.div_ceil(per_elf).max(1) as usize;
+    let mut presents = vec![0u64; limit + 1];
+    for elf in 1..=limit {
+        let houses = (elf..=limit).step_by(elf);
+        let houses = houses.take(visits.map_or(usize::MAX, |v| v as usize));
+        for house in houses {
+            presents[house] += elf as u64 * per_elf;
+        }
+    }
+    (1..=limit).find(|&h| presents[h] >= input).unwrap() as u64
+}
 
-fn part1() {
-    let input = 36000000u64;
-    let bar = ProgressBar::new(input);
-    let (house, presents) = (1..)
+/// The first house that gets at least `input` presents. Reports the most presents seen so far as
+/// the progress.
+fn lowest_house(input: u64, present_count: impl Fn(u64) -> u64, progress: &dyn Progress) -> u64 {
+    progress.start(input);
+    let mut most = 0;
+    let (house, _) = (1..)
         .map(|house| (house, present_count(house)))
-        .map(|v @ (_, p)| {
-            if p > bar.position() {
-                bar.set_position(p);
+        .inspect(|&(_, p)| {
+            if p > most {
+                most = p;
+                progress.set_position(p);
             }
-            v
         })
         .find(|(_, p)| *p >= input)
         .unwrap();
-    bar.finish();
-    println!("Part1: {} gets {}", house, presents);
+    progress.finish();
+    house
 }
 
-fn part2() {
-    let input = 36000000u64;
-    let bar = ProgressBar::new(input);
-    let (house, presents) = (1..)
-        .map(|house| (house, present_count_2(house)))
-        .map(|v @ (_, p)| {
-            if p > bar.position() {
-                bar.set_position(p);
-            }
-            v
-        })
-        .find(|(_, p)| *p >= input)
-        .unwrap();
-    bar.finish();
-    println!("Part2: {} gets {}", house, presents);
-}
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = u64;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_with(|s| number(s, s.trim()))?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(lowest_house(*input, present_count, &*progress::terminal()))
+    }
 
-fn main() {
-    part1();
-    part2();
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(lowest_house(
+            *input,
+            present_count_2,
+            &*progress::terminal(),
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::check_generated;
+    use crate::progress::{Callback, NoProgress};
+    use anyhow::ensure;
+    use std::cell::Cell;
 
     #[test]
     fn test_present_count() {
@@ -83,4 +100,24 @@ mod tests {
         assert_eq!(present_count(8), 10 + 20 + 40 + 80);
         assert_eq!(present_count(9), 10 + 30 + 90);
     }
+
+    #[test]
+    fn test_lowest_house() {
+        let last = Cell::new((0, 0));
+        let progress = Callback::new(|position, total| last.set((position, total)));
+        assert_eq!(lowest_house(70, present_count, &progress), 4);
+        assert_eq!(last.get(), (70, 70));
+    }
+
+    #[test]
+    fn test_sieve_matches_lowest_house() {
+        check_generated(20, 2000, 20, |input| {
+            let input = Day20::parse(input)?;
+            let part1 = lowest_house(input, present_count, &NoProgress);
+            let part2 = lowest_house(input, present_count_2, &NoProgress);
+            ensure!(part1 == lowest_house_sieve(input, 10, None), "part 1");
+            ensure!(part2 == lowest_house_sieve(input, 11, Some(50)), "part 2");
+            Ok(())
+        });
+    }
 }
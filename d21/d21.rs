@@ -1,6 +1,13 @@
+use crate::error::{Expected, number};
+use crate::input::PuzzleInput;
+use crate::solution::{Answer, Solution};
+use anyhow::Context;
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde_json::{Value, json};
 use std::cmp;
+use std::fmt;
+use std::fmt::Formatter;
 use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -12,23 +19,23 @@ struct Item {
 }
 
 impl FromStr for Item {
-    type Err = ();
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // language=regexp
         lazy_static! {
             static ref pat: Regex = Regex::new(r#"^(.*?\S)\s+(\d+)\s+(\d+)\s+(\d+)\s*$"#).unwrap();
         }
-        || -> Option<Self> {
-            let (_, [name, cost, damage, armor]) = pat.captures(s)?.extract();
-            Some(Item {
-                name: name.to_string(),
-                cost: cost.parse().ok()?,
-                damage: damage.parse().ok()?,
-                armor: armor.parse().ok()?,
-            })
-        }()
-        .ok_or(())
+        let (_, [name, cost, damage, armor]) = pat
+            .captures(s)
+            .ok_or_else(|| Expected::new(0, "a name followed by cost, damage and armor"))?
+            .extract();
+        Ok(Item {
+            name: name.to_string(),
+            cost: number(s, cost)?,
+            damage: number(s, damage)?,
+            armor: number(s, armor)?,
+        })
     }
 }
 
@@ -40,29 +47,27 @@ struct Combatant {
 }
 
 impl FromStr for Combatant {
-    type Err = ();
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        || -> Option<Self> {
-            let mut result = Combatant::default();
-            for line in s.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                let mut tokens = line.split(':');
-                let prop = tokens.next()?.trim();
-                let v: i32 = tokens.next()?.trim().parse().ok()?;
-                match prop.to_lowercase().as_str() {
-                    "hit points" => result.hp = v,
-                    "damage" => result.damage = v,
-                    "armor" => result.armor = v,
-                    _ => return None,
-                }
+        let mut result = Combatant::default();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (prop, v) = line
+                .split_once(':')
+                .ok_or_else(|| Expected::end(line, "`:`").within(s, line))?;
+            let (prop, v) = (prop.trim(), number(s, v.trim())?);
+            match prop.to_lowercase().as_str() {
+                "hit points" => result.hp = v,
+                "damage" => result.damage = v,
+                "armor" => result.armor = v,
+                _ => return Err(Expected::at(s, prop, "`Hit Points`, `Damage` or `Armor`")),
             }
-            Some(result)
-        }()
-        .ok_or(())
+        }
+        Ok(result)
     }
 }
 
@@ -89,52 +94,50 @@ struct Items {
 }
 
 impl FromStr for Items {
-    type Err = ();
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        || -> Option<Self> {
-            // language=regexp
-            lazy_static! {
-                static ref header: Regex = Regex::new("^([^:]+):").unwrap();
-            }
+        // language=regexp
+        lazy_static! {
+            static ref header: Regex = Regex::new("^([^:]+):").unwrap();
+        }
+        const HEADERS: &str = "`Weapons:`, `Armor:` or `Rings:`";
 
-            enum State {
-                Begin,
-                Weapons,
-                Armor,
-                Rings,
-            }
-            let mut state = State::Begin;
-            let mut items = Items::default();
+        enum State {
+            Begin,
+            Weapons,
+            Armor,
+            Rings,
+        }
+        let mut state = State::Begin;
+        let mut items = Items::default();
 
-            for line in s.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                if let Some(h) = header.captures(line) {
-                    match h.get(1)?.as_str() {
-                        "Weapons" => state = State::Weapons,
-                        "Armor" => state = State::Armor,
-                        "Rings" => state = State::Rings,
-                        _ => return None,
-                    }
-                    continue;
-                }
-                let item = line.parse().ok()?;
-                match state {
-                    State::Begin => return None,
-                    State::Weapons => items.weapons.push(item),
-                    State::Armor => items.armor.push(item),
-                    State::Rings => items.rings.push(item),
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((_, [h])) = header.captures(line).map(|c| c.extract()) {
+                match h {
+                    "Weapons" => state = State::Weapons,
+                    "Armor" => state = State::Armor,
+                    "Rings" => state = State::Rings,
+                    _ => return Err(Expected::at(s, h, HEADERS)),
                 }
+                continue;
             }
-            if let State::Begin = state {
-                return None;
+            let item = line.parse().map_err(|e: Expected| e.within(s, line))?;
+            match state {
+                State::Begin => return Err(Expected::at(s, line, HEADERS)),
+                State::Weapons => items.weapons.push(item),
+                State::Armor => items.armor.push(item),
+                State::Rings => items.rings.push(item),
             }
-            Some(items)
-        }()
-        .ok_or(())
+        }
+        if let State::Begin = state {
+            return Err(Expected::end(s, HEADERS));
+        }
+        Ok(items)
     }
 }
 
@@ -191,32 +194,95 @@ fn player_wins(mut player: Combatant, mut boss: Combatant) -> bool {
     }
 }
 
-fn main() {
-    let items: Items = include_str!("items.txt").parse().unwrap();
-    let combos = possible_combinations(&items);
-    let player: Combatant = include_str!("player.txt").parse().unwrap();
-    let boss: Combatant = include_str!("boss.txt").parse().unwrap();
-    let mut smallest_cost = i32::MAX;
-    let mut best_loadout = Vec::new();
-    for combo in combos.iter() {
-        let cost: i32 = combo.iter().map(|c| c.cost).sum();
-        if cost < smallest_cost && player_wins(player.clone().with_items(combo), boss.clone()) {
-            smallest_cost = cost;
-            best_loadout = combo.iter().collect();
-        }
+/// The shop, the player's base stats and the boss, which is the actual puzzle input.
+pub struct Game {
+    items: Items,
+    player: Combatant,
+    boss: Combatant,
+}
+
+impl Game {
+    fn loadouts(&self) -> impl Iterator<Item = (i32, Vec<Item>, bool)> {
+        possible_combinations(&self.items).into_iter().map(|combo| {
+            let cost: i32 = combo.iter().map(|c| c.cost).sum();
+            let wins = player_wins(self.player.clone().with_items(&combo), self.boss.clone());
+            (cost, combo, wins)
+        })
+    }
+
+    fn cheapest_winning_loadout(&self) -> Option<(i32, Vec<Item>)> {
+        self.loadouts()
+            .filter(|(_, _, wins)| *wins)
+            .map(|(cost, combo, _)| (cost, combo))
+            .min_by_key(|(cost, _)| *cost)
     }
-    println!("Part1: {}, loadout:\n{:?}", smallest_cost, best_loadout);
-
-    let mut biggest_cost = 0;
-    let mut worst_loadout = Vec::new();
-    for combo in combos.iter() {
-        let cost: i32 = combo.iter().map(|c| c.cost).sum();
-        if cost > biggest_cost && !player_wins(player.clone().with_items(combo), boss.clone()) {
-            biggest_cost = cost;
-            worst_loadout = combo.iter().collect();
+
+    fn most_expensive_losing_loadout(&self) -> Option<(i32, Vec<Item>)> {
+        self.loadouts()
+            .filter(|(_, _, wins)| !*wins)
+            .map(|(cost, combo, _)| (cost, combo))
+            .max_by_key(|(cost, _)| *cost)
+    }
+}
+
+/// What the player bought, and how much it cost.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Loadout {
+    pub cost: i32,
+    pub items: Vec<String>,
+}
+
+impl Loadout {
+    fn new((cost, items): (i32, Vec<Item>)) -> Self {
+        Loadout {
+            cost,
+            items: items.into_iter().map(|item| item.name).collect(),
         }
     }
-    println!("Part2: {}, loadout:\n{:?}", biggest_cost, worst_loadout);
+}
+
+impl fmt::Display for Loadout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cost)
+    }
+}
+
+impl Answer for Loadout {
+    fn artifacts(&self) -> Option<Value> {
+        Some(json!({ "loadout": self.items }))
+    }
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Game;
+    type Part1 = Loadout;
+    type Part2 = Loadout;
+
+    const INPUT_FILE: &'static str = "boss.txt";
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(Game {
+            items: input.companion("items.txt")?.parse_with(str::parse)?,
+            player: input.companion("player.txt")?.parse_with(str::parse)?,
+            boss: input.parse_with(str::parse)?,
+        })
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        input
+            .cheapest_winning_loadout()
+            .map(Loadout::new)
+            .context("the player can't win")
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        input
+            .most_expensive_losing_loadout()
+            .map(Loadout::new)
+            .context("the player can't lose")
+    }
 }
 
 #[cfg(test)]
@@ -411,6 +477,14 @@ mod tests {
         };
         let actual: Result<Combatant, _> = "Hit Points: 100\nDamage: 5\nArmor: 69\n".parse();
         assert_eq!(actual, Ok(expected));
+        assert_eq!(
+            "Hit Points: 100\nMana: 5\n".parse::<Combatant>(),
+            Err(Expected::new(16, "`Hit Points`, `Damage` or `Armor`"))
+        );
+        assert_eq!(
+            "Hit Points: 100\nDamage: x\n".parse::<Combatant>(),
+            Err(Expected::new(24, "a number"))
+        );
     }
 
     #[test]
@@ -427,4 +501,20 @@ mod tests {
         };
         assert!(player_wins(player, boss));
     }
+
+    #[test]
+    fn test_loadout() {
+        let loadout = Loadout::new((
+            33,
+            vec![
+                "Dagger        8     4       0".parse().unwrap(),
+                "Damage +1    25     1       0".parse().unwrap(),
+            ],
+        ));
+        assert_eq!(loadout.to_string(), "33");
+        assert_eq!(
+            loadout.artifacts(),
+            Some(json!({ "loadout": ["Dagger", "Damage +1"] }))
+        );
+    }
 }
@@ -1,10 +1,17 @@
-#![feature(assert_matches)]
-
-use crate::printout::print_turn;
-use aoc2015::graph::a_star_rev;
+use crate::error::Expected;
+use crate::graph::{Limits, Observer, OptimalPaths, Trace, a_star_rev_limited, all_optimal_paths};
+use crate::input::PuzzleInput;
+use crate::repl::{Command, Explore, arg};
+use crate::solution::{Answer, Solution};
+use anyhow::Context;
+use printout::print_turn;
+use serde_json::{Value, json};
 use std::cmp;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::PathBuf;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 enum StatusEffect {
@@ -43,10 +50,20 @@ impl Spell {
             Spell::Recharge => 229,
         }
     }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Spell::MagicMissile => "Magic Missile",
+            Spell::Drain => "Drain",
+            Spell::Shield => "Shield",
+            Spell::Poison => "Poison",
+            Spell::Recharge => "Recharge",
+        }
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
-struct Combatant {
+pub struct Combatant {
     hp: i32,
     damage: i32,
     mana: i32,
@@ -73,13 +90,20 @@ impl Combatant {
         }
     }
 
-    fn boss() -> Self {
-        let config: HashMap<&str, i32> = serde_yaml::from_str(include_str!("boss.txt")).unwrap();
-        Combatant {
-            hp: config["Hit Points"],
-            damage: config["Damage"],
+    fn boss(input: &str) -> Result<Self, Expected> {
+        let config: HashMap<&str, i32> =
+            serde_yaml::from_str(input).map_err(|e| Expected::yaml(&e))?;
+        let get = |key: &str| {
+            config
+                .get(key)
+                .copied()
+                .ok_or_else(|| Expected::end(input, format!("`{key}: <n>`")))
+        };
+        Ok(Combatant {
+            hp: get("Hit Points")?,
+            damage: get("Damage")?,
             ..Combatant::default()
-        }
+        })
     }
 }
 
@@ -185,42 +209,81 @@ fn get_possible_spells(player: &Combatant, boss: &Combatant) -> impl IntoIterato
     .collect::<Vec<_>>()
 }
 
-fn find_best_game(player: Combatant, boss: Combatant) -> (Vec<(GameState, Spell)>, GameState) {
-    fn is_goal(state: &GameState) -> bool {
-        state.boss.hp <= 0
-    }
+fn find_best_game(
+    player: Combatant,
+    boss: Combatant,
+) -> anyhow::Result<(Vec<(GameState, Spell)>, GameState)> {
+    find_best_game_observed(player, boss, &mut ())
+}
 
-    fn get_neighbors(GameState { player, boss }: &GameState) -> Vec<(GameState, Spell)> {
-        get_possible_spells(player, boss)
-            .into_iter()
-            .map(|spell| (game_turn(player.clone(), boss.clone(), spell), spell))
-            .collect::<Vec<_>>()
-    }
+fn is_goal(state: &GameState) -> bool {
+    state.boss.hp <= 0
+}
 
-    fn heuristic(state: &GameState) -> i64 {
-        if state.player.hp <= 0 {
-            return i64::MAX;
-        }
-        if state.boss.hp <= 0 {
-            return 0;
-        }
-        (state.boss.hp * 10 - state.player.mana) as i64
+fn get_neighbors(GameState { player, boss }: &GameState) -> Vec<(GameState, Spell)> {
+    get_possible_spells(player, boss)
+        .into_iter()
+        .map(|spell| (game_turn(player.clone(), boss.clone(), spell), spell))
+        .collect::<Vec<_>>()
+}
+
+fn heuristic(state: &GameState) -> i64 {
+    if state.player.hp <= 0 {
+        return i64::MAX;
     }
+    if state.boss.hp <= 0 {
+        return 0;
+    }
+    (state.boss.hp * 10 - state.player.mana) as i64
+}
 
-    let distance = |_: &GameState, edge: &Spell, _: &GameState| -> i64 { edge.cost() as i64 };
+fn distance(_: &GameState, edge: &Spell, _: &GameState) -> i64 {
+    edge.cost() as i64
+}
 
+/// Like [find_best_game], but tells `observer` about every state the search expands.
+fn find_best_game_observed(
+    player: Combatant,
+    boss: Combatant,
+    observer: &mut impl Observer<GameState, Spell>,
+) -> anyhow::Result<(Vec<(GameState, Spell)>, GameState)> {
     let start = GameState { player, boss };
-    let (best_moves, end_state) =
-        a_star_rev(&start, is_goal, get_neighbors, heuristic, distance).unwrap();
-    let best_moves = best_moves.into_iter().rev().collect();
-    (best_moves, end_state)
+    let found = a_star_rev_limited(
+        &start,
+        is_goal,
+        get_neighbors,
+        heuristic,
+        distance,
+        |_, _| (),
+        &Limits::default(),
+        observer,
+    )
+    .context("the boss can't be beaten")?;
+    let best_moves = found.path_rev.into_iter().rev().collect();
+    Ok((best_moves, found.goal))
+}
+
+/// Every way to win with as little mana as possible. Found without the [heuristic] of
+/// [find_best_game], because it isn't consistent.
+fn find_best_games(
+    player: Combatant,
+    boss: Combatant,
+) -> anyhow::Result<OptimalPaths<GameState, Spell>> {
+    all_optimal_paths(
+        &GameState { player, boss },
+        is_goal,
+        get_neighbors,
+        |_| 0,
+        distance,
+    )
+    .context("the boss can't be beaten")
 }
 
 mod printout {
     use super::*;
 
     fn print_stats_and_effects(game_state: &GameState) {
-        println!(
+        log::debug!(
             "- Player has {} hit points, {} armor, {} mana",
             game_state.player.hp,
             if game_state
@@ -235,7 +298,7 @@ mod printout {
             },
             game_state.player.mana
         );
-        println!("- Boss has {} hit points", game_state.boss.hp);
+        log::debug!("- Boss has {} hit points", game_state.boss.hp);
         for (ttl, effect) in game_state
             .player
             .status_effects
@@ -244,46 +307,45 @@ mod printout {
         {
             match effect {
                 StatusEffect::Cancer => {
-                    println!("Cancer deals 1 damage; its timer is now {}.", ttl)
+                    log::debug!("Cancer deals 1 damage; its timer is now {}.", ttl)
                 }
-                StatusEffect::Armor => println!("Shield's timer is now {}.", ttl),
+                StatusEffect::Armor => log::debug!("Shield's timer is now {}.", ttl),
                 StatusEffect::Poisoned => {
-                    println!("Poison deals 3 damage; its timer is now {}.", ttl)
+                    log::debug!("Poison deals 3 damage; its timer is now {}.", ttl)
                 }
                 StatusEffect::Recharge => {
-                    println!("Recharge provides 101 mana; its timer is now {}.", ttl)
+                    log::debug!("Recharge provides 101 mana; its timer is now {}.", ttl)
                 }
             }
             if *ttl == 0 {
                 match effect {
-                    StatusEffect::Cancer => println!("Cancer wears off."),
-                    StatusEffect::Armor => println!("Shield wears off, decreasing armor by 7."),
-                    StatusEffect::Poisoned => println!("Poison wears off."),
-                    StatusEffect::Recharge => println!("Recharge wears off."),
+                    StatusEffect::Cancer => log::debug!("Cancer wears off."),
+                    StatusEffect::Armor => log::debug!("Shield wears off, decreasing armor by 7."),
+                    StatusEffect::Poisoned => log::debug!("Poison wears off."),
+                    StatusEffect::Recharge => log::debug!("Recharge wears off."),
                 }
             }
         }
     }
 
     pub fn print_turn(game_state: GameState, spell: Spell) {
-        println!("-- Player turn --");
+        log::debug!("-- Player turn --");
         print_stats_and_effects(&game_state);
         match spell {
-            Spell::MagicMissile => println!("Player casts Magic Missile, dealing 4 damage."),
+            Spell::MagicMissile => log::debug!("Player casts Magic Missile, dealing 4 damage."),
             Spell::Drain => {
-                println!("Player casts Drain, dealing 2 damage, and healing 2 hit points.")
+                log::debug!("Player casts Drain, dealing 2 damage, and healing 2 hit points.")
             }
-            Spell::Shield => println!("Player casts Shield, increasing armor by 7."),
-            Spell::Poison => println!("Player casts Poison."),
-            Spell::Recharge => println!("Player casts Recharge."),
+            Spell::Shield => log::debug!("Player casts Shield, increasing armor by 7."),
+            Spell::Poison => log::debug!("Player casts Poison."),
+            Spell::Recharge => log::debug!("Player casts Recharge."),
         };
-        println!();
         let game_state = {
             let player = apply_effects(game_state.player, Turn::Player);
             let boss = apply_effects(game_state.boss, Turn::Player);
             cast_spell(player, boss, spell)
         };
-        println!("-- Boss turn --");
+        log::debug!("-- Boss turn --");
         print_stats_and_effects(&game_state);
         let game_state = {
             let player = apply_effects(game_state.player, Turn::Boss);
@@ -291,32 +353,157 @@ mod printout {
             attack_player(player, boss)
         };
         if game_state.boss.hp <= 0 {
-            println!("Boss is dead.");
+            log::debug!("Boss is dead.");
         } else {
-            println!("Boss attacks for {} damage.", game_state.boss.damage);
+            log::debug!("Boss attacks for {} damage.", game_state.boss.damage);
+        }
+    }
+}
+
+fn mana_used(best_moves: &[(GameState, Spell)]) -> i32 {
+    best_moves.iter().map(|(_, s)| s.cost()).sum()
+}
+
+/// The cheapest way to win, as the spells the player casts in order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BestGame {
+    pub mana: i32,
+    pub spells: Vec<&'static str>,
+}
+
+impl BestGame {
+    fn new(best_moves: &[(GameState, Spell)]) -> Self {
+        BestGame {
+            mana: mana_used(best_moves),
+            spells: best_moves.iter().map(|(_, s)| s.name()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for BestGame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mana)
+    }
+}
+
+impl Answer for BestGame {
+    fn artifacts(&self) -> Option<Value> {
+        Some(json!({ "moves": self.spells }))
+    }
+}
+
+/// The boss, for searching the best game in the REPL and looking at how the search went.
+pub struct Duel {
+    boss: Combatant,
+}
+
+impl Duel {
+    fn player(args: &[&str], index: usize) -> anyhow::Result<Combatant> {
+        match args.get(index) {
+            None => Ok(Combatant::player(false)),
+            Some(&"hard") => Ok(Combatant::player(true)),
+            Some(arg) => anyhow::bail!("expected `hard`, got `{arg}`"),
         }
     }
 }
 
-fn main() {
-    // let player = Combatant::player(false);
-    let player = Combatant::player(true);
-    let boss = Combatant::boss();
-    let before = Instant::now();
-    let (best_moves, _) = find_best_game(player, boss);
-    println!("Elapsed: {:.2?}", before.elapsed());
-    let mana_used: i32 = best_moves.iter().map(|(_, s)| s.cost()).sum();
-    println!("Mana used: {}", mana_used);
-    for (state, spell) in best_moves {
-        print_turn(state, spell);
-        println!();
+impl Explore for Duel {
+    const COMMANDS: &'static [Command<Self>] = &[
+        Command {
+            name: "play",
+            args: "[hard]",
+            help: "Shows the cheapest way to win, in hard mode too.",
+            run: |d, args| {
+                let (best_moves, _) = find_best_game(Duel::player(args, 0)?, d.boss.clone())?;
+                let best_game = BestGame::new(&best_moves);
+                Ok(format!(
+                    "{} mana: {}",
+                    best_game.mana,
+                    best_game.spells.join(", ")
+                ))
+            },
+        },
+        Command {
+            name: "all",
+            args: "[hard]",
+            help: "Lists every way to win with as little mana as possible.",
+            run: |d, args| {
+                let games = find_best_games(Duel::player(args, 0)?, d.boss.clone())?;
+                let ways = match games.count() {
+                    1 => "1 way".to_string(),
+                    n => format!("{n} ways"),
+                };
+                let mut lines = vec![format!("{ways} for {} mana", games.cost())];
+                for (path_rev, _) in games.paths_rev() {
+                    let spells: Vec<_> = path_rev.iter().rev().map(|(_, s)| s.name()).collect();
+                    lines.push(spells.join(", "));
+                }
+                Ok(lines.join("\n"))
+            },
+        },
+        Command {
+            name: "trace",
+            args: "PATH [hard]",
+            help: "Writes every state the search expands to PATH, as DOT for a .dot file and \
+                   JSON lines otherwise.",
+            run: |d, args| {
+                let path: PathBuf = arg(args, 0, "path")?;
+                let mut trace = Trace::default();
+                find_best_game_observed(Duel::player(args, 1)?, d.boss.clone(), &mut trace)?;
+                let text = match path.extension() {
+                    Some(e) if e == "dot" => trace.to_dot(),
+                    _ => trace.to_json_lines(),
+                };
+                fs::write(&path, text)?;
+                Ok(format!(
+                    "{} expansions written to {}",
+                    trace.steps.len(),
+                    path.display()
+                ))
+            },
+        },
+    ];
+
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self> {
+        Ok(Duel {
+            boss: Day22::parse(input)?,
+        })
+    }
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Combatant;
+    type Part1 = BestGame;
+    type Part2 = BestGame;
+
+    const INPUT_FILE: &'static str = "boss.txt";
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_with(Combatant::boss)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let (best_moves, _) = find_best_game(Combatant::player(false), input.clone())?;
+        Ok(BestGame::new(&best_moves))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let (best_moves, _) = find_best_game(Combatant::player(true), input.clone())?;
+        let best_game = BestGame::new(&best_moves);
+        for (state, spell) in best_moves {
+            print_turn(state, spell);
+        }
+        Ok(best_game)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::assert_matches::assert_matches;
+    use crate::graph::{HeuristicError, check_heuristic};
+    use std::assert_matches;
 
     #[test]
     fn test_apply_effects() {
@@ -401,13 +588,17 @@ mod tests {
             damage: 8,
             ..Combatant::default()
         };
-        let (best_moves, end_state) = find_best_game(player, boss);
+        let (best_moves, end_state) = find_best_game(player, boss).unwrap();
         let mana_used: i32 = best_moves.iter().map(|(_, s)| s.cost()).sum();
         assert_eq!(mana_used, 173 + 53);
         assert_eq!(
             best_moves.iter().map(|(_, s)| *s).collect::<Vec<_>>(),
             [Spell::Poison, Spell::MagicMissile]
         );
+        assert_eq!(
+            BestGame::new(&best_moves).artifacts(),
+            Some(json!({ "moves": ["Poison", "Magic Missile"] }))
+        );
         assert_matches!(
             end_state,
             GameState {
@@ -433,7 +624,7 @@ mod tests {
             damage: 8,
             ..Combatant::default()
         };
-        let (best_moves, end_state) = find_best_game(player, boss);
+        let (best_moves, end_state) = find_best_game(player, boss).unwrap();
         let mana_used: i32 = best_moves.iter().map(|(_, s)| s.cost()).sum();
         assert_eq!(mana_used, 229 + 113 + 73 + 173 + 53);
         assert_eq!(
@@ -458,4 +649,87 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_trace() {
+        let boss = Combatant {
+            hp: 14,
+            damage: 8,
+            ..Combatant::default()
+        };
+        let mut trace = Trace::default();
+        let (best_moves, _) =
+            find_best_game_observed(Combatant::player(false), boss, &mut trace).unwrap();
+        let first = &trace.steps[0];
+        assert_eq!((first.g, first.parent.is_none()), (0, true));
+        let last = trace.steps.last().unwrap();
+        assert_eq!(last.g, mana_used(&best_moves) as i64);
+        assert_eq!(last.h, 0);
+        assert!(trace.to_dot().contains("[label=\"Poison\"]"));
+    }
+
+    #[test]
+    fn test_best_games() {
+        let boss = Combatant::boss("Hit Points: 71\nDamage: 10\n").unwrap();
+        let games = find_best_games(Combatant::player(false), boss.clone()).unwrap();
+        let (best_moves, _) = find_best_game(Combatant::player(false), boss).unwrap();
+        assert_eq!(games.cost(), mana_used(&best_moves) as i64);
+        assert_eq!(games.count(), 3);
+        let mut seen = Vec::new();
+        for (path_rev, _) in games.paths_rev() {
+            let spells: Vec<_> = path_rev.iter().rev().map(|(_, s)| *s).collect();
+            assert_eq!(
+                spells.iter().map(|s| s.cost() as i64).sum::<i64>(),
+                games.cost()
+            );
+            assert!(!seen.contains(&spells));
+            seen.push(spells);
+        }
+        assert!(seen.contains(&best_moves.iter().map(|(_, s)| *s).collect()));
+    }
+
+    #[test]
+    fn test_duel() {
+        use crate::repl::Session;
+        let input = PuzzleInput::new("Hit Points: 1000\nDamage: 100\n", "");
+        let mut duel = Duel::load(&input).unwrap();
+        for command in ["all", "play"] {
+            let e = duel.call(command, &[]).unwrap().unwrap_err();
+            assert_eq!(e.to_string(), "the boss can't be beaten");
+        }
+        let e = Day22::part1(&duel.boss).unwrap_err();
+        assert_eq!(
+            format!("{e:#}"),
+            "the boss can't be beaten: no path found after expanding 1 nodes"
+        );
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let boss = Combatant::boss("Hit Points: 71\nDamage: 10\n").unwrap();
+        for hard_mode in [false, true] {
+            let start = GameState {
+                player: Combatant::player(hard_mode),
+                boss: boss.clone(),
+            };
+            let errors =
+                check_heuristic(&start, is_goal, get_neighbors, heuristic, distance, 100_000);
+            // It doesn't overestimate here, so the game found is the cheapest. But it isn't
+            // consistent, because Recharge gives back more mana than most spells cost, so states
+            // can be expanded more than once, and [find_best_games] can't use it.
+            assert!(
+                !errors
+                    .iter()
+                    .any(|e| matches!(e, HeuristicError::Overestimate { .. }))
+            );
+            assert!(!errors.is_empty());
+        }
+    }
+
+    /// The search for part 2 on the real input, `cargo bench bench_hard_mode`.
+    #[bench]
+    fn bench_hard_mode(b: &mut test::Bencher) {
+        let boss = Combatant::boss("Hit Points: 71\nDamage: 10\n").unwrap();
+        b.iter(|| find_best_game(Combatant::player(true), boss.clone()).unwrap());
+    }
 }
@@ -1,10 +1,13 @@
-#![feature(assert_matches)]
-
+use crate::error::Expected;
+use crate::from_str_via_nom;
+use crate::input::PuzzleInput;
+use crate::parse::{PResult, expect, int, keyword, lines, token, ws};
+use crate::repl::{Command, Explore, arg, arg_or};
+use crate::solution::Solution;
 use nom::Parser;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{anychar, char, i32, space0, space1};
-use nom::combinator::{eof, map, value, verify};
+use nom::character::complete::anychar;
+use nom::combinator::{map, value, verify};
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
@@ -41,67 +44,57 @@ impl fmt::Display for Ins {
     }
 }
 
-impl FromStr for Ins {
-    type Err = nom::Err<nom::error::Error<String>>;
+fn register(i: &str) -> PResult<'_, Reg> {
+    expect("a register", verify(anychar, |c| c.is_alphabetic())).parse(i)
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        alt((
-            map(
-                (
-                    alt((
-                        value(Ins::Hlf as fn(Reg) -> Ins, tag("hlf")),
-                        value(Ins::Tpl as fn(Reg) -> Ins, tag("tpl")),
-                        value(Ins::Inc as fn(Reg) -> Ins, tag("inc")),
-                    )),
-                    space1,
-                    verify(anychar, |c| c.is_alphabetic()),
-                    eof,
-                ),
-                |(ins, _, r, _)| ins(r),
+fn ins(i: &str) -> PResult<'_, Ins> {
+    alt((
+        map(
+            (
+                alt((
+                    value(Ins::Hlf as fn(Reg) -> Ins, keyword("hlf")),
+                    value(Ins::Tpl as fn(Reg) -> Ins, keyword("tpl")),
+                    value(Ins::Inc as fn(Reg) -> Ins, keyword("inc")),
+                )),
+                ws(register),
             ),
-            map((tag("jmp"), space1, i32, eof), |(_, _, o, _)| Ins::Jmp(o)),
-            map(
-                (
-                    alt((
-                        value(Ins::Jie as fn(Reg, i32) -> Ins, tag("jie")),
-                        value(Ins::Jio as fn(Reg, i32) -> Ins, tag("jio")),
-                    )),
-                    space1,
-                    verify(anychar, |c| c.is_alphabetic()),
-                    space0,
-                    char(','),
-                    space0,
-                    i32,
-                    eof,
-                ),
-                |(ins, _, r, _, _, _, o, _)| ins(r, o),
+            |(ins, r)| ins(r),
+        ),
+        map((keyword("jmp"), ws(int)), |(_, o)| Ins::Jmp(o)),
+        map(
+            (
+                alt((
+                    value(Ins::Jie as fn(Reg, i32) -> Ins, keyword("jie")),
+                    value(Ins::Jio as fn(Reg, i32) -> Ins, keyword("jio")),
+                )),
+                ws(register),
+                ws(token(",")),
+                ws(int),
             ),
-        ))
-        .parse(s)
-        .map(|(_, o)| o)
-        .map_err(<nom::Err<nom::error::Error<&str>>>::to_owned)
-    }
+            |(ins, r, _, o)| ins(r, o),
+        ),
+    ))
+    .parse(i)
 }
 
-fn parse_program(s: &str) -> Result<Vec<Ins>, nom::Err<nom::error::Error<String>>> {
-    s.lines()
-        .map(str::trim)
-        .filter(|l| !l.is_empty())
-        .map(str::parse)
-        .collect()
+from_str_via_nom!(Ins, ins);
+
+fn parse_program(s: &str) -> Result<Vec<Ins>, Expected> {
+    lines(s, ins)
 }
 
 type Val = i64;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
-struct Cpu {
+pub struct Cpu {
     program: Vec<Ins>,
     pc: i32,
     registers: HashMap<Reg, Val>,
 }
 
 impl FromStr for Cpu {
-    type Err = nom::Err<nom::error::Error<String>>;
+    type Err = Expected;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Cpu {
@@ -122,6 +115,21 @@ impl Cpu {
     }
 }
 
+impl fmt::Display for Cpu {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut registers: Vec<_> = self.registers.iter().collect();
+        registers.sort();
+        write!(f, "pc {}", self.pc)?;
+        for (r, v) in registers {
+            write!(f, ", {r} = {v}")?;
+        }
+        match self.program.get(self.pc as usize) {
+            Some(ins) if self.pc >= 0 => write!(f, ", next: {ins}"),
+            _ => write!(f, ", halted"),
+        }
+    }
+}
+
 fn step(mut cpu: Cpu) -> (Cpu, bool) {
     if cpu.pc < 0 || cpu.pc >= cpu.program.len() as i32 {
         return (cpu, true);
@@ -157,22 +165,131 @@ fn run(mut cpu: Cpu) -> Cpu {
     }
 }
 
-fn main() {
-    let cpu: Cpu = include_str!("input.asm").parse().unwrap();
+/// A CPU that the REPL steps through, and the one it started as.
+pub struct Debugger {
+    start: Cpu,
+    cpu: Cpu,
+    steps: u64,
+}
 
-    let result = run(cpu.clone());
-    println!("Part1: b = {}", result.read('b'));
+impl Explore for Debugger {
+    const COMMANDS: &'static [Command<Self>] = &[
+        Command {
+            name: "step",
+            args: "[COUNT]",
+            help: "Runs one or more instructions.",
+            run: |d, args| {
+                for _ in 0..arg_or(args, 0, "step count", 1)? {
+                    let (cpu, halted) = step(d.cpu.clone());
+                    d.cpu = cpu;
+                    if halted {
+                        break;
+                    }
+                    d.steps += 1;
+                }
+                Ok(format!("{} after {} steps", d.cpu, d.steps))
+            },
+        },
+        Command {
+            name: "run",
+            args: "",
+            help: "Runs until the CPU halts.",
+            run: |d, _| {
+                loop {
+                    let (cpu, halted) = step(d.cpu.clone());
+                    d.cpu = cpu;
+                    if halted {
+                        break Ok(format!("{} after {} steps", d.cpu, d.steps));
+                    }
+                    d.steps += 1;
+                }
+            },
+        },
+        Command {
+            name: "set",
+            args: "REGISTER VALUE",
+            help: "Writes a register.",
+            run: |d, args| {
+                d.cpu
+                    .write(arg(args, 0, "register")?, arg(args, 1, "value")?);
+                Ok(d.cpu.to_string())
+            },
+        },
+        Command {
+            name: "show",
+            args: "",
+            help: "Shows the program counter and the registers.",
+            run: |d, _| Ok(d.cpu.to_string()),
+        },
+        Command {
+            name: "list",
+            args: "",
+            help: "Lists the program, with the next instruction marked.",
+            run: |d, _| {
+                let lines: Vec<_> = d
+                    .cpu
+                    .program
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ins)| {
+                        let marker = if i as i32 == d.cpu.pc { ">" } else { " " };
+                        format!("{marker} {i:>3}  {ins}")
+                    })
+                    .collect();
+                Ok(lines.join("\n"))
+            },
+        },
+        Command {
+            name: "reset",
+            args: "",
+            help: "Goes back to the start of the program with empty registers.",
+            run: |d, _| {
+                d.cpu = d.start.clone();
+                d.steps = 0;
+                Ok(d.cpu.to_string())
+            },
+        },
+    ];
 
-    let mut cpu = cpu;
-    cpu.write('a', 1);
-    let result = run(cpu);
-    println!("Part2: b = {}", result.read('b'));
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self> {
+        let cpu = Day23::parse(input)?;
+        Ok(Debugger {
+            start: cpu.clone(),
+            cpu,
+            steps: 0,
+        })
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Cpu;
+    type Part1 = Val;
+    type Part2 = Val;
+
+    const INPUT_FILE: &'static str = "input.asm";
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_with(str::parse)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(run(input.clone()).read('b'))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let mut cpu = input.clone();
+        cpu.write('a', 1);
+        Ok(run(cpu).read('b'))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::assert_matches::assert_matches;
+    use crate::error::ParseError;
+    use std::assert_matches;
 
     #[test]
     fn test_parse() {
@@ -184,8 +301,17 @@ mod tests {
         assert_eq!("jie a, +10".parse(), Ok(Ins::Jie('a', 10)));
         assert_eq!("jio b, -3".parse(), Ok(Ins::Jio('b', -3)));
 
-        assert_matches!("hlf 1".parse::<Ins>(), Err(_));
-        assert_matches!("jmp abc".parse::<Ins>(), Err(_));
+        assert_eq!("hlf 1".parse::<Ins>(), Err(Expected::new(4, "a register")));
+        assert_eq!("jmp abc".parse::<Ins>(), Err(Expected::new(4, "a number")));
+        assert_matches!("inc a b".parse::<Ins>(), Err(_));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = PuzzleInput::new("inc a\n  jmp abc\n", "").with_name("input.asm");
+        let e = Day23::parse(&input).unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column), (2, 7));
     }
 
     #[test]
@@ -256,4 +382,20 @@ mod tests {
         };
         assert_eq!(run(cpu), expected);
     }
+
+    #[test]
+    fn test_debugger() {
+        use crate::repl::Session;
+        let input = PuzzleInput::new("inc a\njio a, +2\ntpl a\ninc b\n", "");
+        let mut debugger = Debugger::load(&input).unwrap();
+        let mut call = |line: &str| {
+            let words: Vec<_> = line.split(' ').collect();
+            debugger.call(words[0], &words[1..]).unwrap().unwrap()
+        };
+        assert_eq!(call("show"), "pc 0, next: inc a");
+        assert_eq!(call("step 2"), "pc 3, a = 1, next: inc b after 2 steps");
+        assert_eq!(call("run"), "pc 4, a = 1, b = 1, halted after 3 steps");
+        assert_eq!(call("reset"), "pc 0, next: inc a");
+        assert_eq!(call("set a 5"), "pc 0, a = 5, next: inc a");
+    }
 }
@@ -1,4 +1,11 @@
-use indicatif::ProgressBar;
+use crate::error::number;
+use crate::input::PuzzleInput;
+use crate::progress;
+use crate::progress::Progress;
+use crate::solution::{Answer, Solution};
+use serde_json::{Value, json};
+use std::fmt;
+use std::fmt::Formatter;
 
 fn select(nums: &[u32], mask: u64) -> impl Iterator<Item = u32> {
     nums.iter().enumerate().filter_map(move |(i, v)| {
@@ -10,7 +17,7 @@ fn select(nums: &[u32], mask: u64) -> impl Iterator<Item = u32> {
     })
 }
 
-fn lowest_entanglement(nums: &[u32], four: bool) -> (u64, Vec<u32>) {
+fn lowest_entanglement(nums: &[u32], four: bool, progress: &dyn Progress) -> (u64, Vec<u32>) {
     let sum: u32 = nums.iter().sum();
     let n = sum / if four { 4 } else { 3 };
     let mut smallest_first_group_size = nums
@@ -24,9 +31,9 @@ fn lowest_entanglement(nums: &[u32], four: bool) -> (u64, Vec<u32>) {
         + 1;
     let mut smallest_first_group = 0u64;
     let mut lowest_quantum_entanglement = u64::MAX;
-    let pb = ProgressBar::new(1 << nums.len());
+    progress.start(1 << nums.len());
     for mask1 in 0u64..1 << nums.len() {
-        pb.set_position(mask1);
+        progress.set_position(mask1);
         let first_size = mask1.count_ones();
         if first_size > smallest_first_group_size {
             continue;
@@ -63,27 +70,56 @@ fn lowest_entanglement(nums: &[u32], four: bool) -> (u64, Vec<u32>) {
         smallest_first_group = mask1;
         lowest_quantum_entanglement = quantum_entanglement;
     }
-    pb.finish();
+    progress.finish();
     let solution: Vec<_> = select(nums, smallest_first_group).collect();
     (lowest_quantum_entanglement, solution)
 }
 
-fn main() {
-    let nums = include_str!("input.txt")
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<u32>, _>>()
-        .unwrap();
+/// The packages in the passenger compartment, and their quantum entanglement.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FirstGroup {
+    pub quantum_entanglement: u64,
+    pub packages: Vec<u32>,
+}
+
+impl fmt::Display for FirstGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.quantum_entanglement)
+    }
+}
+
+impl Answer for FirstGroup {
+    fn artifacts(&self) -> Option<Value> {
+        Some(json!({ "group": self.packages }))
+    }
+}
+
+pub struct Day24;
 
-    let (lowest_quantum_entanglement, solution) = lowest_entanglement(&nums, false);
-    println!(
-        "lowest QE 3: {} for group {:?}",
-        lowest_quantum_entanglement, solution
-    );
+impl Solution for Day24 {
+    type Input = Vec<u32>;
+    type Part1 = FirstGroup;
+    type Part2 = FirstGroup;
 
-    let (lowest_quantum_entanglement, solution) = lowest_entanglement(&nums, true);
-    println!(
-        "lowest QE 4: {} for group {:?}",
-        lowest_quantum_entanglement, solution
-    );
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        Ok(input.parse_lines_with(|line| number(line, line.trim()))?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let (quantum_entanglement, packages) =
+            lowest_entanglement(input, false, &*progress::terminal());
+        Ok(FirstGroup {
+            quantum_entanglement,
+            packages,
+        })
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let (quantum_entanglement, packages) =
+            lowest_entanglement(input, true, &*progress::terminal());
+        Ok(FirstGroup {
+            quantum_entanglement,
+            packages,
+        })
+    }
 }
@@ -1,3 +1,9 @@
+use crate::error::{Expected, number};
+use crate::input::PuzzleInput;
+use crate::solution::{NoAnswer, Solution};
+use lazy_static::lazy_static;
+use regex::Regex;
+
 /// [v] is \[row, col\]
 fn n2ton(v: [u64; 2]) -> u64 {
     let row = v[0] + v[1] - 1;
@@ -12,8 +18,34 @@ fn get_code(v: [u64; 2]) -> u64 {
     (1..n2ton(v)).fold(20151125u64, |acc, _| hash(acc))
 }
 
-fn main() {
-    println!("Part1: {}", get_code([2978, 3083]));
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = [u64; 2];
+    type Part1 = u64;
+    type Part2 = NoAnswer;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        // language=regexp
+        lazy_static! {
+            static ref pat: Regex = Regex::new(r"row (\d+), column (\d+)").unwrap();
+        }
+        Ok(input.parse_with(|s| {
+            let (_, [row, col]) = pat
+                .captures(s)
+                .ok_or_else(|| Expected::new(0, "`row <n>, column <n>`"))?
+                .extract();
+            Ok([number(s, row)?, number(s, col)?])
+        })?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(get_code(*input))
+    }
+
+    fn part2(_: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(NoAnswer)
+    }
 }
 
 #[cfg(test)]
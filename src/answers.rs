@@ -0,0 +1,130 @@
+use crate::solution::Answers;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+use std::path::Path;
+use std::{fmt, fs};
+
+/// The expected answers of a single day. Parts that aren't known yet are left out.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedAnswers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+/// Expected answers for the real inputs, keyed by day, as stored in `answers.json`:
+///
+/// ```json
+/// { "1": { "part1": "232", "part2": "1783" } }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Registry(pub BTreeMap<u8, ExpectedAnswers>);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected, actual } => {
+                write!(f, "FAIL, expected {expected}, got {actual}")
+            }
+            Verdict::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+pub(crate) fn check(expected: Option<&String>, actual: &str) -> Verdict {
+    match expected {
+        Some(expected) if expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail {
+            expected: expected.clone(),
+            actual: actual.to_string(),
+        },
+        None => Verdict::Missing,
+    }
+}
+
+impl Registry {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("invalid answers in {}", path.display()))
+    }
+
+    pub fn verify(&self, day: u8, answers: &Answers) -> [Verdict; 2] {
+        let expected = self.0.get(&day);
+        [
+            check(expected.and_then(|e| e.part1.as_ref()), &answers.part1),
+            check(expected.and_then(|e| e.part2.as_ref()), &answers.part2),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Inputs;
+
+    #[test]
+    fn test_parse_registry() {
+        let registry: Registry = serde_json::from_str(
+            r#"{"1": {"part1": "232", "part2": "1783"}, "25": {"part1": "7"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            registry.0[&1],
+            ExpectedAnswers {
+                part1: Some("232".to_string()),
+                part2: Some("1783".to_string()),
+            }
+        );
+        assert_eq!(registry.0[&25].part2, None);
+    }
+
+    #[test]
+    fn test_verify() {
+        let registry = Registry(BTreeMap::from([(
+            7,
+            ExpectedAnswers {
+                part1: Some("3176".to_string()),
+                part2: Some("14710".to_string()),
+            },
+        )]));
+        let answers = Answers {
+            part1: "3176".to_string(),
+            part2: "42".to_string(),
+            ..Answers::default()
+        };
+        assert_eq!(
+            registry.verify(7, &answers),
+            [
+                Verdict::Pass,
+                Verdict::Fail {
+                    expected: "14710".to_string(),
+                    actual: "42".to_string(),
+                }
+            ]
+        );
+        assert_eq!(
+            registry.verify(8, &answers),
+            [Verdict::Missing, Verdict::Missing]
+        );
+    }
+
+    #[test]
+    fn test_registry_file_is_valid() {
+        let registry = Registry::load(&Inputs::default().answers_file());
+        assert!(registry.unwrap().0.keys().all(|day| (1..=25).contains(day)));
+    }
+}
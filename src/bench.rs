@@ -0,0 +1,231 @@
+use crate::input::PuzzleInput;
+use crate::solution::{Day, Timings};
+use anyhow::{Context, ensure};
+use serde::{Deserialize, Serialize};
+use std::fmt::Formatter;
+use std::path::Path;
+use std::time::Duration;
+use std::{fmt, fs};
+
+/// Stages faster than this are never reported as regressions, because for them the noise of a
+/// single run easily exceeds any threshold.
+const MIN_REGRESSION: Duration = Duration::from_millis(1);
+
+pub(crate) mod nanos {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(d.as_nanos() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", with = "nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", with = "nanos")]
+    pub median: Duration,
+    #[serde(rename = "max_ns", with = "nanos")]
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Panics if there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub runs: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayBench {
+    fn stages(&self) -> [(&'static str, &Stats); 3] {
+        [
+            ("parse", &self.parse),
+            ("part1", &self.part1),
+            ("part2", &self.part2),
+        ]
+    }
+}
+
+/// Solves the day `runs` times, parsing the input anew every time.
+pub fn bench(day: &Day, input: &PuzzleInput, runs: usize) -> anyhow::Result<DayBench> {
+    ensure!(runs > 0, "need at least one run");
+    let timings = (0..runs)
+        .map(|_| day.run(input).map(|(_, timings)| timings))
+        .collect::<anyhow::Result<Vec<Timings>>>()?;
+    let stats =
+        |stage: fn(&Timings) -> Duration| Stats::from_samples(timings.iter().map(stage).collect());
+    Ok(DayBench {
+        day: day.day,
+        runs,
+        parse: stats(|t| t.parse),
+        part1: stats(|t| t.part1),
+        part2: stats(|t| t.part2),
+    })
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Report(pub Vec<DayBench>);
+
+impl Report {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("invalid benchmark report in {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n").with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// All stages whose median got slower than the baseline's by more than `threshold`, e.g.
+    /// `0.1` for 10%. Days that aren't in both reports are skipped.
+    pub fn regressions(&self, baseline: &Report, threshold: f64) -> Vec<Regression> {
+        let mut result = Vec::new();
+        for current in &self.0 {
+            let Some(base) = baseline.0.iter().find(|b| b.day == current.day) else {
+                continue;
+            };
+            for ((stage, now), (_, then)) in current.stages().into_iter().zip(base.stages()) {
+                if now.median >= MIN_REGRESSION
+                    && now.median.as_secs_f64() > then.median.as_secs_f64() * (1.0 + threshold)
+                {
+                    result.push(Regression {
+                        day: current.day,
+                        stage,
+                        baseline: then.median,
+                        current: now.median,
+                    });
+                }
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<4} {:<6} {:>12} {:>12} {:>12}",
+            "Day", "Stage", "Min", "Median", "Max"
+        )?;
+        for day in &self.0 {
+            for (stage, stats) in day.stages() {
+                writeln!(
+                    f,
+                    "{:<4} {:<6} {:>12} {:>12} {:>12}",
+                    format!("{:02}", day.day),
+                    stage,
+                    format!("{:.2?}", stats.min),
+                    format!("{:.2?}", stats.median),
+                    format!("{:.2?}", stats.max),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub stage: &'static str,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let change = self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0;
+        write!(
+            f,
+            "day {:02} {}: {:.2?} -> {:.2?} ({:+.1}%)",
+            self.day,
+            self.stage,
+            self.baseline,
+            self.current,
+            change * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day_bench(day: u8, part1_median: Duration) -> DayBench {
+        let stats = |median| Stats {
+            min: median,
+            median,
+            max: median,
+        };
+        DayBench {
+            day,
+            runs: 1,
+            parse: stats(Duration::from_micros(5)),
+            part1: stats(part1_median),
+            part2: stats(Duration::from_millis(10)),
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples([5, 1, 4, 2, 3].map(Duration::from_millis).to_vec());
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_report_roundtrip() {
+        let report = Report(vec![day_bench(4, Duration::from_millis(300))]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains(r#""median_ns":300000000"#));
+        assert_eq!(serde_json::from_str::<Report>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = Report(vec![
+            day_bench(4, Duration::from_millis(300)),
+            day_bench(20, Duration::from_millis(300)),
+        ]);
+        let current = Report(vec![
+            day_bench(4, Duration::from_millis(320)),
+            day_bench(20, Duration::from_millis(400)),
+            day_bench(24, Duration::from_millis(400)),
+        ]);
+        assert_eq!(
+            current.regressions(&baseline, 0.1),
+            vec![Regression {
+                day: 20,
+                stage: "part1",
+                baseline: Duration::from_millis(300),
+                current: Duration::from_millis(400),
+            }]
+        );
+        assert_eq!(current.regressions(&baseline, 0.5), vec![]);
+    }
+}
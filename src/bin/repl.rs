@@ -0,0 +1,60 @@
+use anyhow::{Context, bail};
+use aoc2015::days;
+use aoc2015::input::{Inputs, Source};
+use aoc2015::logging::Filter;
+use aoc2015::repl::Repl;
+use clap::Parser;
+use std::io;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Loads a day's input once and lets you call the day's functions on it, like stepping the CPU
+/// of day 23. Type `help` for the commands.
+#[derive(Parser, Debug)]
+#[command(name = "aoc-repl")]
+struct Args {
+    day: u8,
+
+    /// Read the puzzle input from this file instead.
+    #[arg(short, long, value_name = "PATH")]
+    input: Option<Source>,
+
+    /// Directory with one `dNN` directory per day, holding the default inputs.
+    #[arg(long, value_name = "DIR", env = "AOC_INPUT_DIR")]
+    input_dir: Option<PathBuf>,
+
+    /// Which log messages to show, like `info,d07=trace`.
+    #[arg(long, value_name = "FILTER", env = "AOC_LOG", default_value = "warn")]
+    log: Filter,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    aoc2015::logging::init(args.log)?;
+    let source = args.input.unwrap_or_default();
+    if source == Source::Stdin {
+        bail!("the REPL reads commands from stdin, so the input has to come from a file");
+    }
+    let day = days::get(args.day).with_context(|| format!("day {} is not solved", args.day))?;
+    let inputs = args.input_dir.map(Inputs::new).unwrap_or_default();
+    let mut repl = Repl::new(*day, inputs.load(day, &source)?)?;
+
+    let prompt = format!("d{:02}> ", day.day);
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("{prompt}");
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            return Ok(());
+        };
+        match line.trim() {
+            "quit" | "exit" => return Ok(()),
+            line => match repl.eval(line) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{output}"),
+                Err(e) => println!("error: {e:#}"),
+            },
+        }
+    }
+}
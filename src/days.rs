@@ -0,0 +1,150 @@
+use crate::solution::Day;
+use anyhow::{anyhow, bail};
+use std::str::FromStr;
+
+#[path = "../d01/d01.rs"]
+pub mod d01;
+#[path = "../d02/d02.rs"]
+pub mod d02;
+#[path = "../d03/d03.rs"]
+pub mod d03;
+#[path = "../d04/d04.rs"]
+pub mod d04;
+#[path = "../d05/d05.rs"]
+pub mod d05;
+#[path = "../d06/d06.rs"]
+pub mod d06;
+#[path = "../d07/d07.rs"]
+pub mod d07;
+#[path = "../d08/d08.rs"]
+pub mod d08;
+#[path = "../d09/d09.rs"]
+pub mod d09;
+#[path = "../d10/d10.rs"]
+pub mod d10;
+#[path = "../d11/d11.rs"]
+pub mod d11;
+#[path = "../d12/d12.rs"]
+pub mod d12;
+#[path = "../d13/d13.rs"]
+pub mod d13;
+#[path = "../d14/d14.rs"]
+pub mod d14;
+#[path = "../d15/d15.rs"]
+pub mod d15;
+#[path = "../d16/d16.rs"]
+pub mod d16;
+#[path = "../d17/d17.rs"]
+pub mod d17;
+#[path = "../d18/d18.rs"]
+pub mod d18;
+#[path = "../d19/d19.rs"]
+pub mod d19;
+#[path = "../d20/d20.rs"]
+pub mod d20;
+#[path = "../d21/d21.rs"]
+pub mod d21;
+#[path = "../d22/d22.rs"]
+pub mod d22;
+#[path = "../d23/d23.rs"]
+pub mod d23;
+#[path = "../d24/d24.rs"]
+pub mod d24;
+#[path = "../d25/d25.rs"]
+pub mod d25;
+
+pub const DAYS: [Day; 25] = [
+    Day::of::<d01::Day01>(1),
+    Day::of::<d02::Day02>(2),
+    Day::of::<d03::Day03>(3),
+    Day::of::<d04::Day04>(4),
+    Day::of::<d05::Day05>(5),
+    Day::of::<d06::Day06>(6),
+    Day::of::<d07::Day07>(7),
+    Day::of::<d08::Day08>(8),
+    Day::of::<d09::Day09>(9),
+    Day::of::<d10::Day10>(10),
+    Day::of::<d11::Day11>(11),
+    Day::of::<d12::Day12>(12),
+    Day::of::<d13::Day13>(13),
+    Day::of::<d14::Day14>(14),
+    Day::of::<d15::Day15>(15),
+    Day::of::<d16::Day16>(16),
+    Day::of::<d17::Day17>(17),
+    Day::of::<d18::Day18>(18),
+    Day::of::<d19::Day19>(19),
+    Day::of::<d20::Day20>(20),
+    Day::of::<d21::Day21>(21),
+    Day::of::<d22::Day22>(22),
+    Day::of::<d23::Day23>(23),
+    Day::of::<d24::Day24>(24),
+    Day::of::<d25::Day25>(25),
+];
+
+pub fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/// Days picked on the command line: a single day (`7`), a range (`1..=25`, `3..7`) or `all`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Selection(pub Vec<u8>);
+
+impl FromStr for Selection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "all" {
+            return Ok(Selection(DAYS.iter().map(|d| d.day).collect()));
+        }
+        let (from, to): (u8, u8) = if let Some((from, to)) = s.split_once("..=") {
+            (from.parse()?, to.parse()?)
+        } else if let Some((from, to)) = s.split_once("..") {
+            let to: u8 = to.parse()?;
+            (
+                from.parse()?,
+                to.checked_sub(1)
+                    .ok_or_else(|| anyhow!("empty range {s}"))?,
+            )
+        } else {
+            let day = s.parse()?;
+            (day, day)
+        };
+        if from == 0 || to > 25 || from > to {
+            bail!("expected days between 1 and 25, got {s}");
+        }
+        Ok(Selection((from..=to).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_in_order() {
+        assert!(DAYS.iter().map(|d| d.day).eq(1..=25));
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!("7".parse::<Selection>().unwrap(), Selection(vec![7]));
+        assert_eq!(
+            "all".parse::<Selection>().unwrap().0,
+            (1..=25).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "1..=25".parse::<Selection>().unwrap().0,
+            (1..=25).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "3..6".parse::<Selection>().unwrap(),
+            Selection(vec![3, 4, 5])
+        );
+        assert!("0".parse::<Selection>().is_err());
+        assert!("26".parse::<Selection>().is_err());
+        assert!("5..5".parse::<Selection>().is_err());
+        assert!("7..=3".parse::<Selection>().is_err());
+        assert!("seven".parse::<Selection>().is_err());
+    }
+}
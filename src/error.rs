@@ -0,0 +1,235 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// What a parser expected to find, and where. The offset is in bytes and relative to the text the
+/// parser was given, which is usually a single line. [crate::input::PuzzleInput] turns it into a
+/// [ParseError] once it knows where that text sits in the whole input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Expected {
+    pub offset: usize,
+    pub what: String,
+}
+
+impl Expected {
+    pub fn new(offset: usize, what: impl Into<String>) -> Self {
+        Expected {
+            offset,
+            what: what.into(),
+        }
+    }
+
+    /// Points at `part`, which has to be a slice of `s`.
+    pub fn at(s: &str, part: &str, what: impl Into<String>) -> Self {
+        Expected::new(offset_in(s, part), what)
+    }
+
+    /// Points right behind the last character of `s`, for when the text ends too early.
+    pub fn end(s: &str, what: impl Into<String>) -> Self {
+        Expected::new(s.len(), what)
+    }
+
+    /// Converts the error of a nom parser that was run on `s`.
+    pub fn nom(s: &str, e: nom::Err<crate::parse::Error>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => Expected::end(s, "more input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.into_expected(s),
+        }
+    }
+
+    /// Converts the error of deserializing `s` as JSON.
+    pub fn json(s: &str, e: &serde_json::Error) -> Self {
+        let offset = s
+            .split_inclusive('\n')
+            .take(e.line().saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>()
+            + e.column().saturating_sub(1);
+        Expected::new(offset, serde_message(e.to_string(), "JSON"))
+    }
+
+    /// Converts the error of deserializing YAML.
+    pub fn yaml(e: &serde_yaml::Error) -> Self {
+        let offset = e.location().map_or(0, |l| l.index());
+        Expected::new(offset, serde_message(e.to_string(), "YAML"))
+    }
+
+    /// Makes an error from parsing `part` relative to `s` instead, where `part` has to be a slice
+    /// of `s`. This is for parsers that hand single lines to another parser.
+    pub fn within(self, s: &str, part: &str) -> Self {
+        Expected {
+            offset: offset_in(s, part) + self.offset,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} at offset {}", self.what, self.offset)
+    }
+}
+
+impl Error for Expected {}
+
+/// Turns a serde error message into something that reads well after "expected". The location is
+/// left out, because [ParseError] shows that in its own way.
+fn serde_message(message: String, format: &str) -> String {
+    let message = message
+        .rsplit_once(" at line ")
+        .map_or(message.as_str(), |(message, _)| message);
+    match message.strip_prefix("expected ") {
+        Some(expected) => expected.to_string(),
+        None => format!("valid {format} ({message})"),
+    }
+}
+
+/// Where `part`, a slice of `s`, starts in `s`.
+fn offset_in(s: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(s.as_ptr() as usize);
+    debug_assert!(offset <= s.len(), "{part:?} is not a slice of {s:?}");
+    offset
+}
+
+/// Parses `part`, which has to be a slice of `s`, as a number.
+pub fn number<T: FromStr>(s: &str, part: &str) -> Result<T, Expected> {
+    part.parse().map_err(|_| Expected::at(s, part, "a number"))
+}
+
+/// A malformed puzzle input, pointing at the exact spot where parsing failed. Displays like a
+/// compiler error:
+///
+/// ```text
+/// expected a number
+///  --> d02/input.txt:3:5
+///   |
+/// 3 | 1x2xa
+///   |     ^
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    /// Starts at 1.
+    pub line: usize,
+    /// Starts at 1, and counts characters rather than bytes.
+    pub column: usize,
+    /// The whole line that the error is in.
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// `source` is the whole text of `file`, and the offset of `expected` is relative to it.
+    pub fn new(file: impl Into<String>, source: &str, expected: Expected) -> Self {
+        let mut offset = expected.offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+        let text = source[start..end].trim_end_matches('\r');
+        ParseError {
+            file: file.into(),
+            line: source[..start].matches('\n').count() + 1,
+            column: source[start..offset].chars().count() + 1,
+            text: text.to_string(),
+            expected: format!("expected {}", expected.what),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        // Keep tabs, so that the caret lines up no matter how wide they are shown.
+        let indent: String = self
+            .text
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        writeln!(f, "{}", self.expected)?;
+        writeln!(f, "{gutter}--> {}:{}:{}", self.file, self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(f, "{gutter} | {indent}^")
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{token, uint};
+    use nom::Parser;
+
+    #[test]
+    fn test_expected_at() {
+        let line = "1x2xa";
+        assert_eq!(Expected::at(line, &line[4..], "a number").offset, 4);
+        assert_eq!(Expected::end(line, "`x`").offset, 5);
+        assert_eq!(
+            number::<i32>(line, &line[4..]),
+            Err(Expected::new(4, "a number"))
+        );
+        assert_eq!(number::<i32>(line, &line[2..3]), Ok(2));
+    }
+
+    #[test]
+    fn test_within() {
+        let input = "abc\n  def";
+        let line = input.lines().nth(1).unwrap().trim();
+        assert_eq!(Expected::new(1, "x").within(input, line).offset, 7);
+    }
+
+    #[test]
+    fn test_nom() {
+        let line = "12 apples";
+        let e = (uint::<u32>, token(" pears")).parse(line).unwrap_err();
+        assert_eq!(Expected::nom(line, e), Expected::new(2, "` pears`"));
+    }
+
+    #[test]
+    fn test_json() {
+        let json = "[1,\n 2,\n 3 4]";
+        let e = serde_json::from_str::<serde_json::Value>(json).unwrap_err();
+        assert_eq!(Expected::json(json, &e), Expected::new(11, "`,` or `]`"));
+        let e = serde_json::from_str::<serde_json::Value>("[1] 2").unwrap_err();
+        assert_eq!(
+            Expected::json("[1] 2", &e),
+            Expected::new(4, "valid JSON (trailing characters)")
+        );
+    }
+
+    #[test]
+    fn test_location() {
+        let source = "1x2x3\n4x5x6\n7x8xé\n";
+        let e = ParseError::new("gifts.txt", source, Expected::new(16, "a number"));
+        assert_eq!(
+            e,
+            ParseError {
+                file: "gifts.txt".to_string(),
+                line: 3,
+                column: 5,
+                text: "7x8xé".to_string(),
+                expected: "expected a number".to_string(),
+            }
+        );
+        let e = ParseError::new("gifts.txt", source, Expected::new(11, "`x`"));
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 6, "4x5x6"));
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "\n".repeat(9) + "1x2xa\n";
+        let e = ParseError::new("d02/input.txt", &source, Expected::new(13, "a number"));
+        assert_eq!(
+            e.to_string(),
+            "expected a number\n  --> d02/input.txt:10:5\n   |\n10 | 1x2xa\n   |     ^"
+        );
+    }
+}
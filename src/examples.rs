@@ -0,0 +1,126 @@
+use crate::answers::{ExpectedAnswers, Verdict, check};
+use crate::input::{Inputs, PuzzleInput};
+use crate::solution::{Day, Part};
+use anyhow::Context;
+use std::fs;
+use std::path::PathBuf;
+
+/// A sample input from a puzzle's description, stored as `dNN/examples/NAME.txt`. Its expected
+/// answers sit next to it in `NAME.json`, in the same format as one day of `answers.json`:
+///
+/// ```json
+/// { "part1": "605", "part2": "982" }
+/// ```
+///
+/// Parts without an expected answer aren't run, because many examples only fit one of them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+    pub expected: ExpectedAnswers,
+}
+
+impl Inputs {
+    pub fn examples_dir(&self, day: u8) -> PathBuf {
+        self.day_dir(day).join("examples")
+    }
+
+    /// All examples of `day`, sorted by name. A day without an `examples` directory has none.
+    pub fn examples(&self, day: u8) -> anyhow::Result<Vec<Example>> {
+        let dir = self.examples_dir(day);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut examples = Vec::new();
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "txt") {
+                continue;
+            }
+            let answers = path.with_extension("json");
+            let json = fs::read_to_string(&answers)
+                .with_context(|| format!("failed to read {}", answers.display()))?;
+            let expected = serde_json::from_str(&json)
+                .with_context(|| format!("invalid answers in {}", answers.display()))?;
+            examples.push(Example {
+                day,
+                name: path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                path,
+                expected,
+            });
+        }
+        examples.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(examples)
+    }
+}
+
+impl Example {
+    /// Solves the parts that have an expected answer and checks them. The input lives in the
+    /// day's directory as usual, so that days can still read their other files.
+    pub fn run(&self, day: &Day, inputs: &Inputs) -> anyhow::Result<[Verdict; 2]> {
+        let text = fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+        let input = PuzzleInput::new(text, inputs.day_dir(self.day))
+            .with_name(self.path.display().to_string());
+        let verdict = |part, expected: Option<&String>| -> anyhow::Result<Verdict> {
+            match expected {
+                Some(_) => Ok(check(expected, &day.run_part(&input, part)?)),
+                None => Ok(Verdict::Missing),
+            }
+        };
+        Ok([
+            verdict(Part::One, self.expected.part1.as_ref())?,
+            verdict(Part::Two, self.expected.part2.as_ref())?,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_examples() {
+        let inputs = Inputs::default();
+        let mut failures = Vec::new();
+        let mut count = 0;
+        for day in &days::DAYS {
+            for example in inputs.examples(day.day).unwrap() {
+                count += 1;
+                match example.run(day, &inputs) {
+                    Ok(verdicts) => {
+                        for (part, verdict) in verdicts.iter().enumerate() {
+                            if let Verdict::Fail { .. } = verdict {
+                                failures.push(format!(
+                                    "{} part {}: {verdict}",
+                                    example.path.display(),
+                                    part + 1
+                                ));
+                            }
+                        }
+                    }
+                    Err(e) => failures.push(format!("{}: {e:#}", example.path.display())),
+                }
+            }
+        }
+        assert!(count > 0, "no examples found");
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_discover() {
+        let inputs = Inputs::default();
+        let examples = inputs.examples(9).unwrap();
+        assert_eq!(examples[0].name, "sample");
+        assert_eq!(examples[0].expected.part1.as_deref(), Some("605"));
+        assert!(inputs.examples(0).unwrap().is_empty());
+    }
+}
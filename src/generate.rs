@@ -0,0 +1,646 @@
+use crate::days::d16;
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use serde_json::{Map, Value, json};
+use std::fmt::Write;
+
+/// Makes up puzzle inputs for one day, for stress testing and fuzzing. The same seed and size
+/// always give the same input, and every input can be solved.
+#[derive(Copy, Clone, Debug)]
+pub struct Generator {
+    pub day: u8,
+    /// What the size means for this day, like "lines" or "wires".
+    pub size_of: &'static str,
+    /// About as big as the real input, as long as that is still fast to solve.
+    pub default_size: usize,
+    generate: fn(&mut StdRng, usize) -> String,
+}
+
+impl Generator {
+    const fn new(
+        day: u8,
+        size_of: &'static str,
+        default_size: usize,
+        generate: fn(&mut StdRng, usize) -> String,
+    ) -> Self {
+        Generator {
+            day,
+            size_of,
+            default_size,
+            generate,
+        }
+    }
+
+    pub fn generate(&self, seed: u64, size: Option<usize>) -> String {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (self.generate)(&mut rng, size.unwrap_or(self.default_size))
+    }
+}
+
+pub const GENERATORS: [Generator; 25] = [
+    Generator::new(1, "instructions", 7000, d01),
+    Generator::new(2, "presents", 1000, d02),
+    Generator::new(3, "moves", 8192, d03),
+    Generator::new(4, "letters in the key", 8, d04),
+    Generator::new(5, "strings", 1000, d05),
+    Generator::new(6, "instructions", 300, d06),
+    Generator::new(7, "wires", 340, d07),
+    Generator::new(8, "strings", 300, d08),
+    Generator::new(9, "cities", 8, d09),
+    Generator::new(10, "digits", 10, d10),
+    Generator::new(11, "letters in the password", 8, d11),
+    Generator::new(12, "JSON values", 1000, d12),
+    Generator::new(13, "people", 8, d13),
+    Generator::new(14, "reindeer", 9, d14),
+    Generator::new(15, "ingredients", 4, d15),
+    Generator::new(16, "Sues", 500, d16),
+    Generator::new(17, "containers", 20, d17),
+    Generator::new(18, "lights per row", 100, d18),
+    Generator::new(19, "replacement steps", 10, d19),
+    Generator::new(20, "presents", 100_000, d20),
+    Generator::new(21, "boss hit points", 100, d21),
+    Generator::new(22, "boss hit points", 50, d22),
+    Generator::new(23, "instructions before the loop", 40, d23),
+    Generator::new(24, "packages", 16, d24),
+    Generator::new(25, "rows and columns at most", 3000, d25),
+];
+
+pub fn get(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+/// Calls `check` with generated inputs for `day` from the seeds `0..runs`, or as many as the
+/// `AOC_GENERATED_RUNS` environment variable says. Panics with the seed of the first input that
+/// fails, so that it can be looked at with `aoc generate`.
+#[cfg(test)]
+pub(crate) fn check_generated(
+    day: u8,
+    size: usize,
+    runs: u64,
+    mut check: impl FnMut(&crate::input::PuzzleInput) -> anyhow::Result<()>,
+) {
+    let inputs = crate::input::Inputs::default();
+    let runs = std::env::var("AOC_GENERATED_RUNS")
+        .ok()
+        .and_then(|runs| runs.parse().ok())
+        .unwrap_or(runs);
+    for seed in 0..runs {
+        let text = get(day).unwrap().generate(seed, Some(size));
+        let input = crate::input::PuzzleInput::new(text, inputs.day_dir(day));
+        if let Err(e) = check(&input) {
+            panic!("day {day} failed with `aoc generate {day} --seed {seed} --size {size}`: {e:#}");
+        }
+    }
+}
+
+/// A made-up name that is different for every `i`, like `Kalo`.
+fn name(i: usize) -> String {
+    const SYLLABLES: [&str; 8] = ["ka", "lo", "mi", "ra", "ti", "zu", "ne", "fa"];
+    let mut n = i + SYLLABLES.len();
+    let mut name = String::new();
+    while n > 0 {
+        name.insert_str(0, SYLLABLES[n % SYLLABLES.len()]);
+        n /= SYLLABLES.len();
+    }
+    name[..1].to_uppercase() + &name[1..]
+}
+
+/// Lowercase letters that are different for every `i`: `a` to `z`, then `aa`, `ab` and so on.
+fn letters(i: usize) -> String {
+    let mut n = i + 1;
+    let mut s = String::new();
+    while n > 0 {
+        n -= 1;
+        s.insert(0, (b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    s
+}
+
+fn random_letters(rng: &mut StdRng, len: usize) -> String {
+    (0..len)
+        .map(|_| rng.random_range(b'a'..=b'z') as char)
+        .collect()
+}
+
+fn d01(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| if rng.random_bool(0.5) { '(' } else { ')' })
+        .collect()
+}
+
+fn d02(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        let [l, w, h] = [(); 3].map(|_| rng.random_range(1..=30));
+        writeln!(s, "{l}x{w}x{h}").unwrap();
+    }
+    s
+}
+
+fn d03(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| *['^', 'v', '<', '>'].choose(rng).unwrap())
+        .collect()
+}
+
+fn d04(rng: &mut StdRng, size: usize) -> String {
+    random_letters(rng, size)
+}
+
+fn d05(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        writeln!(s, "{}", random_letters(rng, 16)).unwrap();
+    }
+    s
+}
+
+fn d06(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        let action = ["turn on", "turn off", "toggle"].choose(rng).unwrap();
+        let [x0, y0] = [(); 2].map(|_| rng.random_range(0..1000));
+        let [x1, y1] = [x0, y0].map(|v| rng.random_range(v..1000));
+        writeln!(s, "{action} {x0},{y0} through {x1},{y1}").unwrap();
+    }
+    s
+}
+
+/// Every wire only depends on wires that come after it, so there are no cycles, and the last
+/// ones only get signals. `a` depends on `b`, so that overriding `b` matters in part 2.
+fn d07(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(2);
+    let value = |rng: &mut StdRng, i: usize| {
+        if i + 1 < size && rng.random_bool(0.8) {
+            letters(rng.random_range(i + 1..size))
+        } else {
+            rng.random_range(0..=u16::MAX).to_string()
+        }
+    };
+    let mut lines = Vec::new();
+    for i in 0..size {
+        let expression = match rng.random_range(0..5) {
+            _ if i == 0 => format!("b LSHIFT {}", rng.random_range(1..=3)),
+            0 => value(rng, i),
+            1 => format!("NOT {}", value(rng, i)),
+            2 => format!("{} AND {}", value(rng, i), value(rng, i)),
+            3 => format!("{} OR {}", value(rng, i), value(rng, i)),
+            _ => {
+                let op = ["LSHIFT", "RSHIFT"].choose(rng).unwrap();
+                format!("{} {op} {}", value(rng, i), rng.random_range(1..=15))
+            }
+        };
+        lines.push(format!("{expression} -> {}\n", letters(i)));
+    }
+    lines.shuffle(rng);
+    lines.concat()
+}
+
+fn d08(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        s.push('"');
+        for _ in 0..rng.random_range(0..=10) {
+            match rng.random_range(0..6) {
+                0 => s.push_str(r"\\"),
+                1 => s.push_str(r#"\""#),
+                2 => write!(s, r"\x{:02x}", rng.random::<u8>()).unwrap(),
+                _ => {
+                    let len = rng.random_range(1..=4);
+                    s.push_str(&random_letters(rng, len));
+                }
+            }
+        }
+        s.push_str("\"\n");
+    }
+    s
+}
+
+fn d09(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for a in 0..size {
+        for b in a + 1..size {
+            writeln!(
+                s,
+                "{} to {} = {}",
+                name(a),
+                name(b),
+                rng.random_range(1..=150)
+            )
+            .unwrap();
+        }
+    }
+    s
+}
+
+fn d10(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| (b'0' + rng.random_range(1..=3)) as char)
+        .collect()
+}
+
+fn d11(rng: &mut StdRng, size: usize) -> String {
+    random_letters(rng, size)
+}
+
+fn d12(rng: &mut StdRng, size: usize) -> String {
+    fn leaf(rng: &mut StdRng) -> Value {
+        const COLORS: [&str; 6] = ["red", "orange", "yellow", "green", "blue", "violet"];
+        if rng.random_bool(0.5) {
+            json!(rng.random_range(-50..=200))
+        } else {
+            json!(COLORS.choose(rng).unwrap())
+        }
+    }
+
+    fn container(rng: &mut StdRng, budget: &mut usize, depth: usize) -> Value {
+        let len = rng.random_range(1..=5);
+        let mut children = Vec::new();
+        for _ in 0..len {
+            *budget = budget.saturating_sub(1);
+            if *budget == 0 || depth >= 6 || rng.random_bool(0.6) {
+                children.push(leaf(rng));
+            } else {
+                children.push(container(rng, budget, depth + 1));
+            }
+        }
+        if rng.random_bool(0.5) {
+            Value::Array(children)
+        } else {
+            let object: Map<_, _> = children
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| (letters(i), v))
+                .collect();
+            Value::Object(object)
+        }
+    }
+
+    let mut budget = size;
+    let mut top = Vec::new();
+    while budget > 0 {
+        top.push(container(rng, &mut budget, 0));
+    }
+    Value::Array(top).to_string()
+}
+
+fn d13(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for a in 0..size {
+        for b in (0..size).filter(|&b| b != a) {
+            let happiness: i32 = rng.random_range(-100..=100);
+            let change = if happiness < 0 { "lose" } else { "gain" };
+            writeln!(
+                s,
+                "{} would {change} {} happiness units by sitting next to {}.",
+                name(a),
+                happiness.abs(),
+                name(b)
+            )
+            .unwrap();
+        }
+    }
+    s
+}
+
+fn d14(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for i in 0..size {
+        writeln!(
+            s,
+            "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.",
+            name(i),
+            rng.random_range(2..=30),
+            rng.random_range(2..=20),
+            rng.random_range(10..=200)
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// One of the ingredients has exactly 5 calories, so that there is always a cookie with 500.
+fn d15(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let five = rng.random_range(0..size);
+    let mut s = String::new();
+    for i in 0..size {
+        let [capacity, durability, flavor, texture] = [(); 4].map(|_| rng.random_range(-3..=5));
+        let calories = if i == five {
+            5
+        } else {
+            rng.random_range(1..=9)
+        };
+        writeln!(
+            s,
+            "{}: capacity {capacity}, durability {durability}, flavor {flavor}, \
+             texture {texture}, calories {calories}",
+            name(i)
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Exactly one Sue matches what the MFCSAM found for each part. Every other Sue remembers at
+/// least one thing that is wrong either way.
+fn d16(rng: &mut StdRng, size: usize) -> String {
+    const RANGED: [&str; 4] = ["cats", "trees", "pomeranians", "goldfish"];
+    let size = size.max(2);
+    let mut expected: Vec<_> = d16::EXPECTED.iter().map(|(&k, &v)| (k, v)).collect();
+    expected.sort();
+    let (ranged, exact): (Vec<_>, Vec<_>) = expected.iter().partition(|(k, _)| RANGED.contains(k));
+    let part1 = rng.random_range(1..=size);
+    let part2 = loop {
+        let n = rng.random_range(1..=size);
+        if n != part1 {
+            break n;
+        }
+    };
+
+    let mut s = String::new();
+    for n in 1..=size {
+        // The first one decides which part this Sue can match, if any.
+        let first = if n == part1 || n == part2 {
+            *ranged.choose(rng).unwrap()
+        } else {
+            *exact.choose(rng).unwrap()
+        };
+        let mut props = vec![first];
+        while props.len() < 3 {
+            let prop = expected.choose(rng).unwrap();
+            if !props.contains(&prop) {
+                props.push(prop);
+            }
+        }
+        let props = props.into_iter().enumerate().map(|(i, &(prop, v))| {
+            let value = if n == part1 {
+                v
+            } else if n == part2 {
+                match prop {
+                    "cats" | "trees" => rng.random_range(v + 1..=10),
+                    "pomeranians" | "goldfish" => rng.random_range(0..v),
+                    _ => v,
+                }
+            } else if i == 0 {
+                (v + rng.random_range(1..=10)) % 11
+            } else {
+                rng.random_range(0..=10)
+            };
+            format!("{prop}: {value}")
+        });
+        writeln!(s, "Sue {n}: {}", props.collect::<Vec<_>>().join(", ")).unwrap();
+    }
+    s
+}
+
+/// Some of the containers add up to 150 liters, so there is always a way to store the eggnog.
+fn d17(rng: &mut StdRng, size: usize) -> String {
+    let mut containers = Vec::new();
+    let mut left = 150;
+    while left > 50 {
+        let container = rng.random_range(5..=50);
+        containers.push(container);
+        left -= container;
+    }
+    containers.push(left);
+    while containers.len() < size {
+        containers.push(rng.random_range(5..=50));
+    }
+    containers.shuffle(rng);
+    containers.iter().map(|c| format!("{c}\n")).collect()
+}
+
+fn d18(rng: &mut StdRng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        s.extend((0..size).map(|_| if rng.random_bool(0.5) { '#' } else { '.' }));
+        s.push('\n');
+    }
+    s
+}
+
+/// The molecule is made by starting at `e` and applying random replacements, so it can always
+/// be made again.
+fn d19(rng: &mut StdRng, size: usize) -> String {
+    const ELEMENTS: [&str; 12] = [
+        "Al", "B", "Ca", "F", "H", "Mg", "N", "O", "P", "Si", "Th", "Ti",
+    ];
+    let mut rules = Vec::new();
+    for _ in 0..rng.random_range(2..=3) {
+        let to: Vec<_> = ELEMENTS.choose_multiple(rng, 2).copied().collect();
+        rules.push(("e", to));
+    }
+    for from in ELEMENTS {
+        for _ in 0..rng.random_range(1..=2) {
+            let len = rng.random_range(2..=3);
+            let to = (0..len).map(|_| *ELEMENTS.choose(rng).unwrap()).collect();
+            rules.push((from, to));
+        }
+    }
+    rules.dedup();
+
+    let mut molecule = vec!["e"];
+    for _ in 0..size.max(1) {
+        let candidates: Vec<_> = (0..molecule.len())
+            .flat_map(|i| {
+                let rules = &rules;
+                let molecule = &molecule;
+                (0..rules.len()).filter_map(move |r| (rules[r].0 == molecule[i]).then_some((i, r)))
+            })
+            .collect();
+        let Some(&(i, r)) = candidates.choose(rng) else {
+            break;
+        };
+        molecule.splice(i..=i, rules[r].1.iter().copied());
+    }
+
+    let mut s = String::new();
+    for (from, to) in &rules {
+        writeln!(s, "{from} => {}", to.concat()).unwrap();
+    }
+    writeln!(s).unwrap();
+    writeln!(s, "{}", molecule.concat()).unwrap();
+    s
+}
+
+fn d20(rng: &mut StdRng, size: usize) -> String {
+    format!("{}\n", rng.random_range(size / 2..=size.max(1)))
+}
+
+/// Weak enough that the player can win with the right items, and strong enough that they can
+/// lose with the wrong ones.
+fn d21(rng: &mut StdRng, size: usize) -> String {
+    format!(
+        "Hit Points: {}\nDamage: {}\nArmor: {}\n",
+        size.max(1),
+        rng.random_range(4..=9),
+        rng.random_range(0..=3)
+    )
+}
+
+/// Not much stronger than the real boss, so that the player can still win in hard mode.
+fn d22(rng: &mut StdRng, size: usize) -> String {
+    format!(
+        "Hit Points: {}\nDamage: {}\n",
+        size.clamp(1, 71),
+        rng.random_range(5..=10)
+    )
+}
+
+/// Sets up `a` with a random mix of instructions and forward jumps, and then counts the steps of
+/// the Collatz sequence from there in `b`, like the real program. `a` is never 0, so the loop
+/// ends.
+fn d23(rng: &mut StdRng, size: usize) -> String {
+    let mut lines = vec!["inc a".to_string()];
+    let mut triples = 0;
+    while lines.len() < size {
+        let line = match rng.random_range(0..5) {
+            0 if triples < 15 => {
+                triples += 1;
+                "tpl a".to_string()
+            }
+            0 | 1 => "inc a".to_string(),
+            2 => "inc b".to_string(),
+            3 => format!("jmp +{}", rng.random_range(1..=2)),
+            _ => {
+                let jump = ["jie", "jio"].choose(rng).unwrap();
+                format!("{jump} a, +{}", rng.random_range(1..=2))
+            }
+        };
+        lines.push(line);
+    }
+    lines.extend(
+        [
+            "jio a, +8",
+            "inc b",
+            "jie a, +4",
+            "tpl a",
+            "inc a",
+            "jmp +2",
+            "hlf a",
+            "jmp -7",
+        ]
+        .map(String::from),
+    );
+    lines.iter().map(|l| format!("{l}\n")).collect()
+}
+
+/// The packages are laid out in a grid with 3 rows and 4 columns, where all rows weigh the same
+/// and all columns weigh the same. That way they can be split both into three groups and into
+/// four.
+fn d24(rng: &mut StdRng, size: usize) -> String {
+    let k = rng.random_range(20..=60);
+    let mut cells = [[k; 4]; 3];
+    for _ in 0..10 {
+        let [r1, r2] = [0, 1, 2].choose_multiple_array(rng).unwrap();
+        let [c1, c2] = [0, 1, 2, 3].choose_multiple_array(rng).unwrap();
+        let x = rng.random_range(1..=k / 2);
+        if cells[r1][c2] > x && cells[r2][c1] > x {
+            cells[r1][c1] += x;
+            cells[r2][c2] += x;
+            cells[r1][c2] -= x;
+            cells[r2][c1] -= x;
+        }
+    }
+    // Splitting a package in two keeps the rows and columns as they were.
+    let mut packages: Vec<u32> = cells.into_iter().flatten().collect();
+    while packages.len() < size.min(60) {
+        let i = rng.random_range(0..packages.len());
+        if packages[i] > 1 {
+            let part = rng.random_range(1..packages[i]);
+            packages[i] -= part;
+            packages.push(part);
+        }
+    }
+    packages.sort();
+    packages.iter().map(|p| format!("{p}\n")).collect()
+}
+
+fn d25(rng: &mut StdRng, size: usize) -> String {
+    let [row, column] = [(); 2].map(|_| rng.random_range(1..=size.max(1)));
+    format!(
+        "To continue, please consult the code grid in the manual.  Enter the code at row {row}, \
+         column {column}.\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+    use crate::input::{Inputs, PuzzleInput};
+
+    #[test]
+    fn test_generators_are_in_order() {
+        assert!(GENERATORS.iter().map(|g| g.day).eq(1..=25));
+    }
+
+    #[test]
+    fn test_deterministic() {
+        for generator in &GENERATORS {
+            assert_eq!(
+                generator.generate(7, None),
+                generator.generate(7, None),
+                "day {}",
+                generator.day
+            );
+        }
+        assert_ne!(
+            get(7).unwrap().generate(1, None),
+            get(7).unwrap().generate(2, None)
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(name(0), "Loka");
+        assert_ne!(name(7), name(8));
+        assert_eq!(letters(0), "a");
+        assert_eq!(letters(25), "z");
+        assert_eq!(letters(26), "aa");
+    }
+
+    /// Small inputs of the days that are quick to solve, which have to parse and solve without
+    /// errors.
+    #[test]
+    fn test_solvable() {
+        let inputs = Inputs::default();
+        for (day, size) in [
+            (1, None),
+            (2, None),
+            (3, None),
+            (5, None),
+            (6, Some(20)),
+            (7, None),
+            (8, None),
+            (9, None),
+            (12, None),
+            (13, None),
+            (14, None),
+            (15, Some(3)),
+            (16, None),
+            (17, Some(12)),
+            (18, Some(10)),
+            (19, Some(5)),
+            (20, Some(1000)),
+            (21, None),
+            (23, None),
+            (24, Some(12)),
+            (25, None),
+        ] {
+            for seed in 0..3 {
+                let text = get(day).unwrap().generate(seed, size);
+                let input = PuzzleInput::new(text, inputs.day_dir(day));
+                let result = days::get(day).unwrap().run(&input);
+                assert!(
+                    result.is_ok(),
+                    "day {day} seed {seed}: {:#}",
+                    result.unwrap_err()
+                );
+            }
+        }
+    }
+}
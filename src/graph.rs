@@ -1,7 +1,15 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt::Formatter;
+use serde_json::json;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Formatter, Write};
 use std::hash::Hash;
-use std::{error, fmt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use std::{cmp, error, fmt};
+
+// All searches take the same closures: `get_neighbors` returns the nodes next to one, each with
+// the edge that leads there, and `distance` says how long such an edge is. Paths come back in
+// reverse order, as a list of `(node, edge taken from it)`, starting next to the goal.
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
 pub struct NoPathFound;
@@ -14,8 +22,299 @@ impl fmt::Display for NoPathFound {
 
 impl error::Error for NoPathFound {}
 
+/// How much work a search did.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub expanded: u64,
+    /// The most entries the open set had at once, outdated ones included.
+    pub max_open: usize,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes expanded, at most {} open, {:.2?}",
+            self.expanded, self.max_open, self.elapsed
+        )
+    }
+}
+
+/// When a search should give up. Nothing is limited by default.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Limits<'a> {
+    pub max_expansions: Option<u64>,
+    /// How many nodes the search may remember, whether they were expanded or not.
+    pub max_nodes: Option<usize>,
+    pub deadline: Option<Instant>,
+    /// Stops the search once it is set, e.g. from another thread.
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+impl Limits<'_> {
+    fn check(&self, stats: &Stats, nodes: usize) -> Option<Stop> {
+        if self.max_expansions.is_some_and(|max| stats.expanded >= max) {
+            Some(Stop::Expansions)
+        } else if self.max_nodes.is_some_and(|max| nodes > max) {
+            Some(Stop::Nodes)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(Stop::Deadline)
+        } else if self
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            Some(Stop::Cancelled)
+        } else {
+            None
+        }
+    }
+}
+
+/// Which of the [Limits] stopped a search.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Stop {
+    Expansions,
+    Nodes,
+    Deadline,
+    Cancelled,
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Stop::Expansions => write!(f, "too many expansions"),
+            Stop::Nodes => write!(f, "too many nodes"),
+            Stop::Deadline => write!(f, "out of time"),
+            Stop::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Why [a_star_rev_limited] didn't find the goal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SearchError<Node, Edge> {
+    /// Every node that can be reached was expanded.
+    NoPath { stats: Stats },
+    /// One of the [Limits] was hit. `best` is the expanded node that the heuristic thought was
+    /// closest to the goal, and `path_rev` is the way there.
+    Stopped {
+        reason: Stop,
+        best: Node,
+        path_rev: Vec<(Node, Edge)>,
+        stats: Stats,
+    },
+}
+
+impl<Node, Edge> SearchError<Node, Edge> {
+    pub fn stats(&self) -> &Stats {
+        match self {
+            SearchError::NoPath { stats } | SearchError::Stopped { stats, .. } => stats,
+        }
+    }
+}
+
+impl<Node, Edge> fmt::Display for SearchError<Node, Edge> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::NoPath { stats } => {
+                write!(f, "no path found after expanding {} nodes", stats.expanded)
+            }
+            SearchError::Stopped { reason, stats, .. } => write!(
+                f,
+                "search stopped, {reason}, after expanding {} nodes",
+                stats.expanded
+            ),
+        }
+    }
+}
+
+impl<Node: fmt::Debug, Edge: fmt::Debug> error::Error for SearchError<Node, Edge> {}
+
+/// What [a_star_rev_limited] found.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Found<Node, Edge> {
+    /// The path in reverse, as returned by [a_star_rev].
+    pub path_rev: Vec<(Node, Edge)>,
+    pub goal: Node,
+    pub stats: Stats,
+}
+
+/// Gets told about every node a search expands, like a [Trace]. `()` ignores everything.
+pub trait Observer<Node, Edge> {
+    /// `node` is about to be expanded. It is `g` away from the start, the heuristic says it is
+    /// `h` away from the goal, and it was reached from `parent` over the edge next to it.
+    fn expanded(&mut self, node: &Node, g: i64, h: i64, parent: Option<(&Node, &Edge)>);
+}
+
+impl<Node, Edge> Observer<Node, Edge> for () {
+    fn expanded(&mut self, _: &Node, _: i64, _: i64, _: Option<(&Node, &Edge)>) {}
+}
+
+/// One expansion in a [Trace].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step<Node, Edge> {
+    pub node: Node,
+    pub g: i64,
+    pub h: i64,
+    pub parent: Option<(Node, Edge)>,
+}
+
+/// Records every expansion of a search, so that it can be looked at afterwards with
+/// [Trace::to_dot] or [Trace::to_json_lines]. Nodes and edges are written with their [Debug]
+/// output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trace<Node, Edge> {
+    pub steps: Vec<Step<Node, Edge>>,
+}
+
+// Not derived, so that nodes and edges don't have to be Default.
+impl<Node, Edge> Default for Trace<Node, Edge> {
+    fn default() -> Self {
+        Trace { steps: Vec::new() }
+    }
+}
+
+impl<Node: Clone, Edge: Clone> Observer<Node, Edge> for Trace<Node, Edge> {
+    fn expanded(&mut self, node: &Node, g: i64, h: i64, parent: Option<(&Node, &Edge)>) {
+        self.steps.push(Step {
+            node: node.clone(),
+            g,
+            h,
+            parent: parent.map(|(n, e)| (n.clone(), e.clone())),
+        });
+    }
+}
+
+impl<Node: Debug + Eq + Hash, Edge: Debug> Trace<Node, Edge> {
+    /// The explored tree for Graphviz, with one box per expansion that says when it happened
+    /// and what g and h were. A node that was expanded again over a cheaper way gets another box.
+    pub fn to_dot(&self) -> String {
+        fn escape(s: String) -> String {
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+        let mut dot = String::from("digraph search {\n    node [shape=box];\n");
+        let mut latest = HashMap::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            let h = match step.h {
+                i64::MAX => "inf".to_string(),
+                h => h.to_string(),
+            };
+            let label = format!(
+                "#{i} {}\\ng={} h={h}",
+                escape(format!("{:?}", step.node)),
+                step.g
+            );
+            let _ = writeln!(dot, "    n{i} [label=\"{label}\"];");
+            if let Some((parent, edge)) = &step.parent
+                && let Some(p) = latest.get(parent)
+            {
+                let edge = escape(format!("{edge:?}"));
+                let _ = writeln!(dot, "    n{p} -> n{i} [label=\"{edge}\"];");
+            }
+            latest.insert(&step.node, i);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// One JSON object per expansion, like
+    /// `{"edge":null,"g":0,"h":3,"node":"1","parent":null,"step":0}`.
+    pub fn to_json_lines(&self) -> String {
+        let mut lines = String::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            let (parent, edge) = match &step.parent {
+                Some((parent, edge)) => (Some(format!("{parent:?}")), Some(format!("{edge:?}"))),
+                None => (None, None),
+            };
+            let line = json!({
+                "step": i,
+                "node": format!("{:?}", step.node),
+                "g": step.g,
+                "h": step.h,
+                "parent": parent,
+                "edge": edge,
+            });
+            let _ = writeln!(lines, "{line}");
+        }
+        lines
+    }
+}
+
+/// What a search knows about a node it has reached.
+struct Visit<Node, Edge> {
+    node: Node,
+    /// The cost of the cheapest way here found so far.
+    g_score: i64,
+    came_from: Option<(usize, Edge)>,
+    /// Set once the node has been expanded, so that outdated heap entries can be skipped.
+    closed: bool,
+}
+
+/// Every node a search has reached, in the order they were first reached.
+struct Search<Node, Edge> {
+    visits: Vec<Visit<Node, Edge>>,
+    indices: HashMap<Node, usize>,
+    stats: Stats,
+    /// The expanded node with the lowest heuristic.
+    best: usize,
+}
+
+impl<Node: Clone + Eq + Hash, Edge: Clone> Search<Node, Edge> {
+    fn new(start: &Node) -> Self {
+        Search {
+            visits: vec![Visit {
+                node: start.clone(),
+                g_score: 0,
+                came_from: None,
+                closed: false,
+            }],
+            indices: HashMap::from([(start.clone(), 0)]),
+            stats: Stats::default(),
+            best: 0,
+        }
+    }
+
+    fn add(&mut self, node: Node, g_score: i64, came_from: (usize, Edge)) -> usize {
+        self.indices.insert(node.clone(), self.visits.len());
+        self.visits.push(Visit {
+            node,
+            g_score,
+            came_from: Some(came_from),
+            closed: false,
+        });
+        self.visits.len() - 1
+    }
+
+    /// The way from the start to the node at `index`, in reverse.
+    fn path_rev(&self, mut index: usize) -> Vec<(Node, Edge)> {
+        let mut path = Vec::new();
+        while let Some((prev, edge)) = &self.visits[index].came_from {
+            path.push((self.visits[*prev].node.clone(), edge.clone()));
+            index = *prev;
+        }
+        path
+    }
+
+    fn found(self, goal: Option<usize>) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound> {
+        let goal = goal.ok_or(NoPathFound)?;
+        Ok((self.path_rev(goal), self.visits[goal].node.clone()))
+    }
+}
+
 /// returns the path in reverse order because it might be needed, and it would be inefficient to
 /// reverse it twice in that case.
+///
+/// The open set is a binary heap of `(f_score, node)` entries. Instead of updating an entry when a
+/// cheaper way to its node turns up, another one is pushed, and the outdated one is skipped when
+/// it comes up. A heuristic of [i64::MAX] marks nodes that can't lead to the goal; they are only
+/// expanded once nothing else is left.
+///
+/// Nodes with the same `f_score` are expanded in the order they were pushed, so the same input
+/// always gives the same path. See [a_star_rev_with] for other ways to break ties.
 pub fn a_star_rev<Node, Edge, Neighbors>(
     start: &Node,
     is_goal: impl Fn(&Node) -> bool,
@@ -28,56 +327,1056 @@ where
     Edge: Clone,
     Neighbors: IntoIterator<Item = (Node, Edge)>,
 {
-    let mut open_set = HashSet::from([start.clone()]);
-    let mut came_from = HashMap::<_, (Node, Edge)>::new();
-    let mut g_score = HashMap::from([(start.clone(), 0i64)]);
-    let mut f_score = HashMap::from([(start.clone(), heuristic(start))]);
+    a_star_rev_with(
+        start,
+        is_goal,
+        get_neighbors,
+        heuristic,
+        distance,
+        |_, _| (),
+    )
+}
+
+/// Like [a_star_rev], but nodes with the same `f_score` are expanded lowest `tie_break` first,
+/// and only then in the order they were pushed. It gets the node and its `g_score`, so
+/// `|_, g| Reverse(g)` prefers nodes that are further along, which often finds the goal sooner.
+pub fn a_star_rev_with<Node, Edge, Neighbors, Key>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    tie_break: impl Fn(&Node, i64) -> Key,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    Key: Ord,
+{
+    a_star_rev_limited(
+        start,
+        is_goal,
+        get_neighbors,
+        heuristic,
+        distance,
+        tie_break,
+        &Limits::default(),
+        &mut (),
+    )
+    .map(|found| (found.path_rev, found.goal))
+    .map_err(|_| NoPathFound)
+}
+
+/// Like [a_star_rev_with], but gives up once it hits one of the `limits`, and says how much work
+/// it did either way. Every expansion is also passed to `observer`, which can be `&mut ()`.
+#[allow(clippy::too_many_arguments)]
+pub fn a_star_rev_limited<Node, Edge, Neighbors, Key>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    tie_break: impl Fn(&Node, i64) -> Key,
+    limits: &Limits,
+    observer: &mut impl Observer<Node, Edge>,
+) -> Result<Found<Node, Edge>, SearchError<Node, Edge>>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    Key: Ord,
+{
+    let (search, end) = best_first(
+        start,
+        is_goal,
+        get_neighbors,
+        heuristic,
+        distance,
+        tie_break,
+        limits,
+        observer,
+    );
+    match end {
+        Ok(goal) => Ok(Found {
+            path_rev: search.path_rev(goal),
+            goal: search.visits[goal].node.clone(),
+            stats: search.stats,
+        }),
+        Err(None) => Err(SearchError::NoPath {
+            stats: search.stats,
+        }),
+        Err(Some(reason)) => Err(SearchError::Stopped {
+            reason,
+            best: search.visits[search.best].node.clone(),
+            path_rev: search.path_rev(search.best),
+            stats: search.stats,
+        }),
+    }
+}
+
+/// The search behind [a_star_rev_limited] and [dijkstra]. Returns everything it reached, and the
+/// goal if it found one, or the limit that stopped it.
+#[allow(clippy::too_many_arguments)]
+fn best_first<Node, Edge, Neighbors, Key>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    tie_break: impl Fn(&Node, i64) -> Key,
+    limits: &Limits,
+    observer: &mut impl Observer<Node, Edge>,
+) -> (Search<Node, Edge>, Result<usize, Option<Stop>>)
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    Key: Ord,
+{
+    let f_score = |g_score: i64, node: &Node| match heuristic(node) {
+        i64::MAX => i64::MAX,
+        h => g_score + h,
+    };
+
+    let mut search = Search::new(start);
+    // Counts the pushes, so that the order they happened in is the last tie-breaker.
+    let mut pushes = 0u64;
+    let mut open_set = BinaryHeap::from([Reverse((
+        f_score(0, start),
+        tie_break(start, 0),
+        pushes,
+        0usize,
+    ))]);
+
+    let started = Instant::now();
+    let mut best_h = i64::MAX;
+    let end = loop {
+        let Some(Reverse((f, _, _, current))) = open_set.pop() else {
+            break Err(None);
+        };
+        if search.visits[current].closed {
+            continue;
+        }
+        if let Some(stop) = limits.check(&search.stats, search.visits.len()) {
+            break Err(Some(stop));
+        }
+        search.visits[current].closed = true;
+        search.stats.expanded += 1;
+        let visit = &search.visits[current];
+        let h = match f {
+            i64::MAX => i64::MAX,
+            f => f - visit.g_score,
+        };
+        if h < best_h {
+            best_h = h;
+            search.best = current;
+        }
+        let parent = visit
+            .came_from
+            .as_ref()
+            .map(|(p, edge)| (&search.visits[*p].node, edge));
+        observer.expanded(&visit.node, visit.g_score, h, parent);
+
+        if is_goal(&visit.node) {
+            break Ok(current);
+        }
+
+        let g_score = visit.g_score;
+        for (neighbor, edge) in get_neighbors(&search.visits[current].node) {
+            let tentative_g_score =
+                g_score + distance(&search.visits[current].node, &edge, &neighbor);
+            let index = match search.indices.get(&neighbor) {
+                Some(&index) if tentative_g_score >= search.visits[index].g_score => continue,
+                Some(&index) => {
+                    let visit = &mut search.visits[index];
+                    visit.g_score = tentative_g_score;
+                    visit.came_from = Some((current, edge));
+                    visit.closed = false;
+                    index
+                }
+                None => search.add(neighbor, tentative_g_score, (current, edge)),
+            };
+            let neighbor = &search.visits[index].node;
+            let f = f_score(tentative_g_score, neighbor);
+            let key = tie_break(neighbor, tentative_g_score);
+            pushes += 1;
+            open_set.push(Reverse((f, key, pushes, index)));
+        }
+        search.stats.max_open = cmp::max(search.stats.max_open, open_set.len());
+    };
+    search.stats.elapsed = started.elapsed();
+    log::debug!("{}", search.stats);
+    (search, end)
+}
+
+/// Like [a_star_rev], but with iterative deepening: it goes depth first, turning back at nodes
+/// whose f_score is over a threshold, and starts over with a higher one until it finds the goal.
+/// That visits nodes many times over, but only the current path is kept in memory, so it works
+/// where remembering every node doesn't fit. Nodes with a heuristic of [i64::MAX] are never
+/// expanded, and the heuristic should be admissible for the path to be the shortest.
+pub fn ida_star_rev<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    ida_star_rev_bounded(start, is_goal, get_neighbors, heuristic, distance, 0)
+}
+
+/// Like [ida_star_rev], but uses memory for up to `max_nodes` nodes to remember the cheapest way
+/// to them in the current iteration. Reaching one of those again, and no cheaper, cuts the path
+/// off there, which saves going through the same part of the graph over and over. With a
+/// `max_nodes` big enough for the whole graph this needs about as much memory as [a_star_rev].
+pub fn ida_star_rev_bounded<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    max_nodes: usize,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    /// A node on the current path, with the edge that led to it and the neighbors that are left.
+    struct Frame<Node, Edge, I> {
+        node: Node,
+        g_score: i64,
+        edge: Option<Edge>,
+        neighbors: I,
+    }
+
+    if is_goal(start) {
+        return Ok((Vec::new(), start.clone()));
+    }
+    let mut threshold = match heuristic(start) {
+        i64::MAX => return Err(NoPathFound),
+        h => h,
+    };
+    let mut stats = Stats::default();
+    let started = Instant::now();
+    for iteration in 1.. {
+        let mut next_threshold = i64::MAX;
+        let mut on_path = HashSet::from([start.clone()]);
+        let mut cheapest = HashMap::new();
+        let mut stack = vec![Frame {
+            node: start.clone(),
+            g_score: 0,
+            edge: None,
+            neighbors: get_neighbors(start).into_iter(),
+        }];
+        stats.expanded += 1;
+        while let Some(frame) = stack.last_mut() {
+            let Some((neighbor, edge)) = frame.neighbors.next() else {
+                let frame = stack.pop().unwrap();
+                on_path.remove(&frame.node);
+                continue;
+            };
+            if on_path.contains(&neighbor) {
+                continue;
+            }
+            let g_score = frame.g_score + distance(&frame.node, &edge, &neighbor);
+            let known = cheapest.get(&neighbor).copied();
+            if known.is_some_and(|g| g_score >= g) {
+                continue;
+            }
+            if known.is_some() || cheapest.len() < max_nodes {
+                cheapest.insert(neighbor.clone(), g_score);
+            }
+            let f_score = match heuristic(&neighbor) {
+                i64::MAX => continue,
+                h => g_score + h,
+            };
+            if f_score > threshold {
+                next_threshold = cmp::min(next_threshold, f_score);
+                continue;
+            }
+            if is_goal(&neighbor) {
+                let mut path_rev = vec![(stack[stack.len() - 1].node.clone(), edge)];
+                for (frame, next) in stack.iter().zip(&stack[1..]).rev() {
+                    path_rev.push((frame.node.clone(), next.edge.clone().unwrap()));
+                }
+                stats.elapsed = started.elapsed();
+                log::debug!("{stats}, {iteration} iterations");
+                return Ok((path_rev, neighbor));
+            }
+            stats.expanded += 1;
+            on_path.insert(neighbor.clone());
+            let neighbors = get_neighbors(&neighbor).into_iter();
+            stack.push(Frame {
+                node: neighbor,
+                g_score,
+                edge: Some(edge),
+                neighbors,
+            });
+            stats.max_open = cmp::max(stats.max_open, stack.len());
+        }
+        if next_threshold == i64::MAX {
+            break;
+        }
+        threshold = next_threshold;
+    }
+    Err(NoPathFound)
+}
+
+/// A node that [all_optimal_paths] reached.
+struct Reached<Node, Edge> {
+    node: Node,
+    g_score: i64,
+    /// The edges that lead here on a shortest path. Only the start has none.
+    parents: Vec<(usize, Edge)>,
+}
+
+/// Every shortest path to the goal, as found by [all_optimal_paths]. They share their nodes, so
+/// there can be far more paths than nodes.
+pub struct OptimalPaths<Node, Edge> {
+    nodes: Vec<Reached<Node, Edge>>,
+    /// The goals that can be reached as cheaply as possible.
+    goals: Vec<usize>,
+    cost: i64,
+}
+
+impl<Node: Clone, Edge: Clone> OptimalPaths<Node, Edge> {
+    /// How long every one of the paths is.
+    pub fn cost(&self) -> i64 {
+        self.cost
+    }
+
+    pub fn goals(&self) -> impl Iterator<Item = &Node> {
+        self.goals.iter().map(|&i| &self.nodes[i].node)
+    }
+
+    /// How many paths there are, without going through them one by one.
+    pub fn count(&self) -> u64 {
+        let mut order: Vec<_> = (0..self.nodes.len()).collect();
+        // Edges aren't empty, so the edges into a node all come from nodes with a lower g_score.
+        order.sort_by_key(|&i| self.nodes[i].g_score);
+        let mut counts = vec![0u64; self.nodes.len()];
+        for i in order {
+            counts[i] = match &self.nodes[i].parents[..] {
+                [] => 1,
+                parents => parents
+                    .iter()
+                    .fold(0, |sum, &(p, _)| sum.saturating_add(counts[p])),
+            };
+        }
+        self.goals
+            .iter()
+            .fold(0, |sum, &goal| sum.saturating_add(counts[goal]))
+    }
+
+    /// Every path, in reverse like the one from [a_star_rev], along with its goal.
+    pub fn paths_rev(&self) -> impl Iterator<Item = (Vec<(Node, Edge)>, Node)> {
+        // The path being looked at, from the goal back to the start, as each node with which of
+        // its edges the path takes into it. Going to the next path is counting up in this.
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        let mut goals = self.goals.iter();
+        std::iter::from_fn(move || {
+            if stack.pop().is_none() {
+                stack.push((*goals.next()?, 0));
+            } else {
+                // The start was on top, so the last entry under it with another edge to take
+                // goes on with that one.
+                loop {
+                    let Some((index, edge)) = stack.pop() else {
+                        stack.push((*goals.next()?, 0));
+                        break;
+                    };
+                    if edge + 1 < self.nodes[index].parents.len() {
+                        stack.push((index, edge + 1));
+                        break;
+                    }
+                }
+            }
+            while let Some(&(p, _)) = stack
+                .last()
+                .and_then(|&(i, e)| self.nodes[i].parents.get(e))
+            {
+                stack.push((p, 0));
+            }
+            let path_rev = stack
+                .iter()
+                .zip(&stack[1..])
+                .map(|(&(index, edge), &(p, _))| {
+                    let edge = &self.nodes[index].parents[edge].1;
+                    (self.nodes[p].node.clone(), edge.clone())
+                })
+                .collect();
+            Some((path_rev, self.nodes[stack[0].0].node.clone()))
+        })
+    }
+}
+
+/// Like [a_star_rev], but finds every shortest path instead of just one, and every goal that
+/// can be reached as cheaply. The heuristic has to be consistent, which `|_| 0` always is, and
+/// edges must be longer than 0.
+pub fn all_optimal_paths<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Result<OptimalPaths<Node, Edge>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let f_score = |g_score: i64, node: &Node| match heuristic(node) {
+        i64::MAX => i64::MAX,
+        h => g_score + h,
+    };
 
-    while let Some(current) = open_set
-        .iter()
-        .min_by_key(|&s| f_score.get(s).copied().unwrap_or(i64::MAX))
+    let mut nodes = vec![Reached {
+        node: start.clone(),
+        g_score: 0,
+        parents: Vec::new(),
+    }];
+    let mut indices = HashMap::from([(start.clone(), 0)]);
+    let mut closed = vec![false];
+    let mut pushes = 0u64;
+    let mut open_set = BinaryHeap::from([Reverse((f_score(0, start), pushes, 0usize))]);
+    let mut goals = Vec::new();
+    let mut cost = None;
+    while let Some(Reverse((f, _, current))) = open_set.pop() {
+        if f == i64::MAX || cost.is_some_and(|cost| f > cost) {
+            break;
+        }
+        if closed[current] {
+            continue;
+        }
+        closed[current] = true;
+        let g_score = nodes[current].g_score;
+        if is_goal(&nodes[current].node) {
+            cost = Some(g_score);
+            goals.push(current);
+            continue;
+        }
+        for (neighbor, edge) in get_neighbors(&nodes[current].node) {
+            let tentative_g_score = g_score + distance(&nodes[current].node, &edge, &neighbor);
+            let index = match indices.get(&neighbor) {
+                Some(&index) if tentative_g_score > nodes[index].g_score => continue,
+                Some(&index) if tentative_g_score == nodes[index].g_score => {
+                    nodes[index].parents.push((current, edge));
+                    continue;
+                }
+                Some(&index) => {
+                    nodes[index].g_score = tentative_g_score;
+                    nodes[index].parents = vec![(current, edge)];
+                    closed[index] = false;
+                    index
+                }
+                None => {
+                    indices.insert(neighbor.clone(), nodes.len());
+                    nodes.push(Reached {
+                        node: neighbor,
+                        g_score: tentative_g_score,
+                        parents: vec![(current, edge)],
+                    });
+                    closed.push(false);
+                    nodes.len() - 1
+                }
+            };
+            pushes += 1;
+            open_set.push(Reverse((
+                f_score(tentative_g_score, &nodes[index].node),
+                pushes,
+                index,
+            )));
+        }
+    }
+    Ok(OptimalPaths {
+        nodes,
+        goals,
+        cost: cost.ok_or(NoPathFound)?,
+    })
+}
+
+/// One of the paths from [k_shortest_paths_rev].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RankedPath<Node, Edge> {
+    pub cost: i64,
+    /// The path in reverse, as returned by [a_star_rev].
+    pub path_rev: Vec<(Node, Edge)>,
+    pub goal: Node,
+}
+
+/// The `k` shortest paths from `start` to a goal, shortest first, each with its length, using
+/// Yen's algorithm. Paths never visit a node twice. Each one after the first is the cheapest
+/// that branches off one of the earlier ones, found with [dijkstra]-like searches that avoid the
+/// nodes before the branch and the edges the earlier paths take there. Edges must not be
+/// negative.
+pub fn k_shortest_paths_rev<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    k: usize,
+) -> Vec<RankedPath<Node, Edge>>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone + Eq,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    // Paths here go forward, as the nodes and the edges between them.
+    type Forward<Node, Edge> = (Vec<Node>, Vec<Edge>);
+    let length = |(nodes, edges): &Forward<Node, Edge>| -> i64 {
+        edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge)| distance(&nodes[i], edge, &nodes[i + 1]))
+            .sum()
+    };
+    // Edges are avoided along with where they lead, since different ones can be equal.
+    let shortest = |from: &Node, avoid_nodes: &HashSet<Node>, avoid_edges: &[(&Node, &Edge)]| {
+        a_star_rev(
+            from,
+            &is_goal,
+            |node| {
+                get_neighbors(node)
+                    .into_iter()
+                    .filter(|(next, edge)| {
+                        !avoid_nodes.contains(next)
+                            && (node != from || !avoid_edges.contains(&(next, edge)))
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |_| 0,
+            &distance,
+        )
+        .ok()
+        .map(|(path_rev, goal)| {
+            let mut nodes: Vec<_> = path_rev.iter().rev().map(|(n, _)| n.clone()).collect();
+            nodes.push(goal);
+            let edges = path_rev.into_iter().rev().map(|(_, e)| e).collect();
+            (nodes, edges)
+        })
+    };
+
+    let mut found: Vec<(i64, Forward<Node, Edge>)> = Vec::new();
+    let mut candidates: Vec<(i64, Forward<Node, Edge>)> = Vec::new();
+    if k > 0
+        && let Some(path) = shortest(start, &HashSet::new(), &[])
     {
-        if is_goal(current) {
-            let mut total_path = Vec::new();
-            let goal = current.clone();
-            let mut current = current;
-            while came_from.contains_key(current) {
-                let prev = came_from.get(current).unwrap();
-                current = &prev.0;
-                total_path.push(prev.clone());
-            }
-            return Ok((total_path, goal));
-        }
-
-        let current = current.clone();
-        open_set.remove(&current);
-
-        for (neighbor, edge) in get_neighbors(&current) {
-            let tentative_g_score = g_score
-                .get(&current)
-                .map(|s| *s + distance(&current, &edge, &neighbor))
-                .unwrap_or(i64::MAX);
-            if tentative_g_score < g_score.get(&neighbor).copied().unwrap_or(i64::MAX) {
-                came_from.insert(neighbor.clone(), (current.clone(), edge.clone()));
-                g_score.insert(neighbor.clone(), tentative_g_score);
-                let h = heuristic(&neighbor);
-                let h = if h == i64::MAX {
-                    i64::MAX
-                } else {
-                    tentative_g_score + h
+        candidates.push((length(&path), path));
+    }
+    while found.len() < k && !candidates.is_empty() {
+        // The cheapest candidate, and the one found first of those.
+        let best = (0..candidates.len())
+            .min_by_key(|&i| candidates[i].0)
+            .unwrap();
+        found.push(candidates.remove(best));
+        let (_, (nodes, edges)) = &found[found.len() - 1];
+        for spur in 0..edges.len() {
+            let root = (&nodes[..=spur], &edges[..spur]);
+            let avoid_edges: Vec<_> = found
+                .iter()
+                .filter(|(_, (n, e))| n.len() > spur + 1 && (&n[..=spur], &e[..spur]) == root)
+                .map(|(_, (n, e))| (&n[spur + 1], &e[spur]))
+                .collect();
+            let avoid_nodes: HashSet<_> = nodes[..spur].iter().cloned().collect();
+            let Some((spur_nodes, spur_edges)) = shortest(&nodes[spur], &avoid_nodes, &avoid_edges)
+            else {
+                continue;
+            };
+            let path = (
+                [&nodes[..spur], &spur_nodes].concat(),
+                [&edges[..spur], &spur_edges].concat(),
+            );
+            if !candidates.iter().chain(&found).any(|(_, p)| *p == path) {
+                candidates.push((length(&path), path));
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(cost, (mut nodes, edges))| {
+            let goal = nodes.pop().unwrap();
+            RankedPath {
+                cost,
+                path_rev: nodes.into_iter().zip(edges).rev().collect(),
+                goal,
+            }
+        })
+        .collect()
+}
+
+/// Something wrong with a heuristic, as found by [check_heuristic].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HeuristicError<Node, Edge> {
+    /// The heuristic says `node` is `h` away from the goal, but it is only `actual` away, so
+    /// [a_star_rev] might not find the shortest path.
+    Overestimate { node: Node, h: i64, actual: i64 },
+    /// The heuristic drops by more than the edge from `from` to `to` is long, so [a_star_rev]
+    /// might expand nodes again, and [all_optimal_paths] might miss paths.
+    Inconsistent {
+        from: Node,
+        edge: Edge,
+        to: Node,
+        h_from: i64,
+        h_to: i64,
+        distance: i64,
+    },
+}
+
+impl<Node: Debug, Edge: Debug> fmt::Display for HeuristicError<Node, Edge> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HeuristicError::Overestimate { node, h, actual } => {
+                write!(
+                    f,
+                    "h({node:?}) = {h}, but it is {actual} away from the goal"
+                )
+            }
+            HeuristicError::Inconsistent {
+                from,
+                edge,
+                to,
+                h_from,
+                h_to,
+                distance,
+            } => write!(
+                f,
+                "h({from:?}) = {h_from}, but {edge:?} is {distance} long and leads to {to:?} \
+                 with h = {h_to}"
+            ),
+        }
+    }
+}
+
+/// Checks a heuristic for [a_star_rev] against the real distances to the goal, which it finds by
+/// going through the whole graph from `start`, so this is for small instances in tests. Returns
+/// every node where the heuristic overestimates, and then every edge where it isn't consistent,
+/// both in the order they were reached. A heuristic of [i64::MAX] is only wrong if the goal can be
+/// reached. Only the first `max_nodes` nodes are looked at, which can hide some mistakes but
+/// never reports one that isn't there.
+pub fn check_heuristic<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> i64,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    max_nodes: usize,
+) -> Vec<HeuristicError<Node, Edge>>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    // The graph, as far as it goes from the start, without going past goals.
+    let mut nodes = vec![start.clone()];
+    let mut indices = HashMap::from([(start.clone(), 0)]);
+    let mut edges = Vec::new();
+    let mut current = 0;
+    while current < nodes.len() {
+        if !is_goal(&nodes[current]) {
+            for (neighbor, edge) in get_neighbors(&nodes[current]) {
+                let to = match indices.get(&neighbor) {
+                    Some(&to) => to,
+                    None if nodes.len() < max_nodes => {
+                        indices.insert(neighbor.clone(), nodes.len());
+                        nodes.push(neighbor);
+                        nodes.len() - 1
+                    }
+                    None => continue,
                 };
-                f_score.insert(neighbor.clone(), h);
-                open_set.insert(neighbor);
+                let d = distance(&nodes[current], &edge, &nodes[to]);
+                edges.push((current, edge, to, d));
+            }
+        }
+        current += 1;
+    }
+
+    // The distance from every node to the nearest goal, by going backwards from all of them.
+    let mut incoming = vec![Vec::new(); nodes.len()];
+    for &(from, _, to, d) in &edges {
+        incoming[to].push((from, d));
+    }
+    let mut actual = vec![None; nodes.len()];
+    let mut open_set: BinaryHeap<_> = (0..nodes.len())
+        .filter(|&i| is_goal(&nodes[i]))
+        .map(|i| Reverse((0, i)))
+        .collect();
+    while let Some(Reverse((d, current))) = open_set.pop() {
+        if actual[current].is_some() {
+            continue;
+        }
+        actual[current] = Some(d);
+        for &(from, edge) in &incoming[current] {
+            if actual[from].is_none() {
+                open_set.push(Reverse((d + edge, from)));
             }
         }
     }
 
+    let h: Vec<_> = nodes.iter().map(&heuristic).collect();
+    let mut errors = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(actual) = actual[i]
+            && h[i] > actual
+        {
+            errors.push(HeuristicError::Overestimate {
+                node: node.clone(),
+                h: h[i],
+                actual,
+            });
+        }
+    }
+    for (from, edge, to, distance) in edges {
+        if h[from] != i64::MAX && h[to] != i64::MAX && h[from] > distance + h[to] {
+            errors.push(HeuristicError::Inconsistent {
+                from: nodes[from].clone(),
+                edge,
+                to: nodes[to].clone(),
+                h_from: h[from],
+                h_to: h[to],
+                distance,
+            });
+        }
+    }
+    errors
+}
+
+/// The shortest distances from one node to all the others that can be reached, as found by
+/// [dijkstra].
+pub struct Distances<Node, Edge>(Search<Node, Edge>);
+
+impl<Node: Clone + Eq + Hash, Edge: Clone> Distances<Node, Edge> {
+    /// How far `node` is from the start, or `None` if it can't be reached.
+    pub fn get(&self, node: &Node) -> Option<i64> {
+        self.0.indices.get(node).map(|&i| self.0.visits[i].g_score)
+    }
+
+    /// A shortest path from the start to `node`, in reverse like the one from [a_star_rev].
+    pub fn path_rev(&self, node: &Node) -> Option<Vec<(Node, Edge)>> {
+        self.0.indices.get(node).map(|&i| self.0.path_rev(i))
+    }
+
+    /// Every node that can be reached, with its distance, in the order they were first reached.
+    pub fn iter(&self) -> impl Iterator<Item = (&Node, i64)> {
+        self.0.visits.iter().map(|v| (&v.node, v.g_score))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.visits.len()
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.0.stats
+    }
+
+    /// Never true, because the start can always be reached.
+    pub fn is_empty(&self) -> bool {
+        self.0.visits.is_empty()
+    }
+}
+
+/// The shortest distances from `start` to every node that can be reached from it. Edges must
+/// not be negative.
+pub fn dijkstra<Node, Edge, Neighbors>(
+    start: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Distances<Node, Edge>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let (search, _) = best_first(
+        start,
+        |_| false,
+        get_neighbors,
+        |_| 0,
+        distance,
+        |_, _| (),
+        &Limits::default(),
+        &mut (),
+    );
+    Distances(search)
+}
+
+/// Finds a path with the fewest edges, for when they are all equally long. Neighbors are tried
+/// in the order `get_neighbors` returns them.
+pub fn bfs_rev<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let mut search = Search::new(start);
+    let mut queue = VecDeque::from([0]);
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&search.visits[current].node) {
+            return search.found(Some(current));
+        }
+        let level = search.visits[current].g_score + 1;
+        for (neighbor, edge) in get_neighbors(&search.visits[current].node) {
+            if !search.indices.contains_key(&neighbor) {
+                queue.push_back(search.add(neighbor, level, (current, edge)));
+            }
+        }
+    }
     Err(NoPathFound)
 }
 
+/// Every node that can be reached from `start`, grouped by how many edges away it is. The first
+/// level is only the start.
+pub fn bfs_levels<Node, Edge, Neighbors>(
+    start: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> Vec<Vec<Node>>
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut levels = vec![vec![start.clone()]];
+    loop {
+        let next: Vec<_> = levels[levels.len() - 1]
+            .iter()
+            .flat_map(&get_neighbors)
+            .filter_map(|(node, _)| seen.insert(node.clone()).then_some(node))
+            .collect();
+        if next.is_empty() {
+            return levels;
+        }
+        levels.push(next);
+    }
+}
+
+/// One side of a [bidirectional_rev] search.
+struct Frontier<Node, Edge> {
+    search: Search<Node, Edge>,
+    /// `(g_score, push order, index)`, cheapest first, with outdated entries skipped like in
+    /// [a_star_rev].
+    open_set: BinaryHeap<Reverse<(i64, u64, usize)>>,
+    pushes: u64,
+}
+
+impl<Node: Clone + Eq + Hash, Edge: Clone> Frontier<Node, Edge> {
+    fn new(start: &Node) -> Self {
+        Frontier {
+            search: Search::new(start),
+            open_set: BinaryHeap::from([Reverse((0, 0, 0))]),
+            pushes: 0,
+        }
+    }
+
+    /// The g_score of the next node to expand, or `None` if there is none.
+    fn peek(&mut self) -> Option<i64> {
+        while let Some(&Reverse((g, _, index))) = self.open_set.peek() {
+            let visit = &self.search.visits[index];
+            if !visit.closed && visit.g_score == g {
+                return Some(g);
+            }
+            self.open_set.pop();
+        }
+        None
+    }
+
+    /// Expands the next node, and returns the cheapest way through an edge from it to a node
+    /// that `other` has reached, as `(cost, index here, index there)`. `distance` gets the nodes
+    /// in the order this side goes.
+    fn expand<Neighbors>(
+        &mut self,
+        other: &Search<Node, Edge>,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    ) -> Option<(i64, usize, usize)>
+    where
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+    {
+        let Reverse((g_score, _, current)) = self.open_set.pop()?;
+        self.search.visits[current].closed = true;
+        self.search.stats.expanded += 1;
+        let mut meeting: Option<(i64, usize, usize)> = None;
+        for (neighbor, edge) in get_neighbors(&self.search.visits[current].node) {
+            let tentative_g_score =
+                g_score + distance(&self.search.visits[current].node, &edge, &neighbor);
+            let index = match self.search.indices.get(&neighbor) {
+                Some(&index) if tentative_g_score >= self.search.visits[index].g_score => continue,
+                Some(&index) => {
+                    let visit = &mut self.search.visits[index];
+                    visit.g_score = tentative_g_score;
+                    visit.came_from = Some((current, edge));
+                    visit.closed = false;
+                    index
+                }
+                None => self
+                    .search
+                    .add(neighbor, tentative_g_score, (current, edge)),
+            };
+            if let Some(&there) = other.indices.get(&self.search.visits[index].node) {
+                let cost = tentative_g_score + other.visits[there].g_score;
+                if meeting.is_none_or(|(best, _, _)| cost < best) {
+                    meeting = Some((cost, index, there));
+                }
+            }
+            self.pushes += 1;
+            self.open_set
+                .push(Reverse((tentative_g_score, self.pushes, index)));
+        }
+        self.search.stats.max_open = cmp::max(self.search.stats.max_open, self.open_set.len());
+        meeting
+    }
+}
+
+/// Finds a shortest path from `start` to `goal` by searching from both ends at once, until the
+/// two searches meet in the middle. `get_predecessors` goes the other way than `get_neighbors`:
+/// it returns the nodes that lead to a node, each with the edge from there. `distance` always
+/// gets the nodes in the direction of the edge, and must not be negative. With
+/// `|_, _, _| 1` this is a bidirectional breadth-first search.
+///
+/// The side with fewer open nodes goes next, so this pays off when one direction branches much
+/// more than the other. The path comes back in reverse, like the one from [a_star_rev].
+pub fn bidirectional_rev<Node, Edge, Forward, Backward>(
+    start: &Node,
+    goal: &Node,
+    get_neighbors: impl Fn(&Node) -> Forward,
+    get_predecessors: impl Fn(&Node) -> Backward,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Result<Vec<(Node, Edge)>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Forward: IntoIterator<Item = (Node, Edge)>,
+    Backward: IntoIterator<Item = (Node, Edge)>,
+{
+    if start == goal {
+        return Ok(Vec::new());
+    }
+    let mut forward = Frontier::new(start);
+    // Here `came_from` points the other way, to the next node on the way to the goal.
+    let mut backward = Frontier::new(goal);
+    // The cheapest way found so far, as `(cost, forward index, backward index)`.
+    let mut best: Option<(i64, usize, usize)> = None;
+    while let (Some(f), Some(b)) = (forward.peek(), backward.peek()) {
+        if best.is_some_and(|(cost, _, _)| f + b >= cost) {
+            break;
+        }
+        let meeting = if forward.open_set.len() <= backward.open_set.len() {
+            forward.expand(&backward.search, &get_neighbors, &distance)
+        } else {
+            backward
+                .expand(&forward.search, &get_predecessors, |n, e, p| {
+                    distance(p, e, n)
+                })
+                .map(|(cost, b, f)| (cost, f, b))
+        };
+        if let Some(meeting) = meeting
+            && best.is_none_or(|(cost, _, _)| meeting.0 < cost)
+        {
+            best = Some(meeting);
+        }
+    }
+    log::debug!(
+        "{} nodes expanded forward, {} backward",
+        forward.search.stats.expanded,
+        backward.search.stats.expanded
+    );
+
+    let (_, meet_forward, meet_backward) = best.ok_or(NoPathFound)?;
+    let backward = &backward.search;
+    let mut path = Vec::new();
+    let mut index = meet_backward;
+    while let Some((next, edge)) = &backward.visits[index].came_from {
+        path.push((backward.visits[index].node.clone(), edge.clone()));
+        index = *next;
+    }
+    path.reverse();
+    path.extend(forward.search.path_rev(meet_forward));
+    Ok(path)
+}
+
+/// Finds some path, going as deep as it can first. It isn't the shortest, but it only needs
+/// memory for the nodes it has seen and the ones waiting on the stack.
+pub fn dfs_rev<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let (search, goal) = dfs(start, is_goal, get_neighbors);
+    search.found(goal)
+}
+
+/// Every node that can be reached from `start`, in the order a depth-first search finds them.
+pub fn flood_fill<Node, Edge, Neighbors>(
+    start: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> Vec<Node>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let (search, _) = dfs(start, |_| false, get_neighbors);
+    search.visits.into_iter().map(|v| v.node).collect()
+}
+
+/// The search behind [dfs_rev] and [flood_fill]. A node is only added to the search once it is
+/// taken off the stack, so that its path is the one that was actually followed. Neighbors are
+/// tried in the order `get_neighbors` returns them.
+fn dfs<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> (Search<Node, Edge>, Option<usize>)
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let mut search = Search::new(start);
+    let mut stack = vec![];
+    let mut current = 0;
+    loop {
+        if is_goal(&search.visits[current].node) {
+            return (search, Some(current));
+        }
+        let depth = search.visits[current].g_score + 1;
+        let neighbors: Vec<_> = get_neighbors(&search.visits[current].node)
+            .into_iter()
+            .collect();
+        stack.extend(
+            neighbors
+                .into_iter()
+                .rev()
+                .map(|(node, edge)| (node, depth, (current, edge))),
+        );
+        current = loop {
+            let Some((node, depth, came_from)) = stack.pop() else {
+                return (search, None);
+            };
+            if !search.indices.contains_key(&node) {
+                break search.add(node, depth, came_from);
+            }
+        };
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::assert_matches;
 
     #[test]
     fn test_a_star() {
@@ -111,4 +1410,546 @@ mod tests {
         let path: Vec<usize> = result.iter().rev().map(|(n, _)| *n).chain([goal]).collect();
         assert_eq!(path, vec![0, 5, 2]);
     }
+
+    #[test]
+    fn test_a_star_cheaper_way_found_later() {
+        // 0 reaches 2 directly for 10, or through 1 for 2, which is only found after 2 was
+        // queued the first time. 3 can't lead to the goal.
+        let edges: [&[(usize, i64)]; _] = [&[(2, 10), (1, 1), (3, 1)], &[(2, 1)], &[(4, 1)], &[]];
+        let (path, goal) = a_star_rev(
+            &0,
+            |n| *n == 4,
+            |a| edges.get(*a).copied().unwrap_or_default().to_vec(),
+            |a| if *a == 3 { i64::MAX } else { 0 },
+            |_, cost, _| *cost,
+        )
+        .unwrap();
+        assert_eq!(goal, 4);
+        let path: Vec<usize> = path.iter().rev().map(|(n, _)| *n).collect();
+        assert_eq!(path, vec![0, 1, 2]);
+        assert_eq!(
+            a_star_rev(&3, |n| *n == 4, |_| [(0, ())], |_| 0, |_, _, _| 1).unwrap_err(),
+            NoPathFound
+        );
+    }
+
+    /// All the shortest paths through a 3x3 grid, from the top left to the bottom right corner.
+    fn grid_path<Key: Ord>(tie_break: impl Fn(&(i32, i32), i64) -> Key) -> Vec<(i32, i32)> {
+        let (path, goal) = a_star_rev_with(
+            &(0, 0),
+            |&n| n == (2, 2),
+            |&(x, y)| {
+                [(x + 1, y), (x, y + 1)]
+                    .into_iter()
+                    .filter(|&(x, y)| x <= 2 && y <= 2)
+                    .map(|n| (n, ()))
+            },
+            |&(x, y)| (4 - x - y) as i64,
+            |_, _, _| 1,
+            tie_break,
+        )
+        .unwrap();
+        path.iter().rev().map(|(n, _)| *n).chain([goal]).collect()
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let first = grid_path(|_, _| ());
+        for _ in 0..10 {
+            assert_eq!(grid_path(|_, _| ()), first);
+        }
+        assert_eq!(
+            grid_path(|&(_, y), _| y),
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]
+        );
+        assert_eq!(
+            grid_path(|&(x, _), _| x),
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    /// 0 -> 1 -> 3 -> 4 and 0 -> 2 -> 4, with the edge lengths as edges, and 5 on its own.
+    fn small_graph(n: &usize) -> Vec<(usize, i64)> {
+        match n {
+            0 => vec![(1, 1), (2, 5)],
+            1 => vec![(3, 1)],
+            2 => vec![(4, 1)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    fn nodes(path: &[(usize, i64)], goal: usize) -> Vec<usize> {
+        path.iter().rev().map(|(n, _)| *n).chain([goal]).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (path, goal) = bfs_rev(&0, |n| *n == 4, small_graph).unwrap();
+        assert_eq!(nodes(&path, goal), vec![0, 2, 4]);
+        assert_eq!(path[0], (2, 1));
+        assert_eq!(bfs_rev(&0, |n| *n == 5, small_graph), Err(NoPathFound));
+        assert_eq!(
+            bfs_levels(&0, small_graph),
+            vec![vec![0], vec![1, 2], vec![3, 4]]
+        );
+    }
+
+    #[test]
+    fn test_bidirectional() {
+        let predecessors = |n: &usize| match n {
+            1 => vec![(0, 1)],
+            2 => vec![(0, 5)],
+            3 => vec![(1, 1)],
+            4 => vec![(2, 1), (3, 1)],
+            _ => vec![],
+        };
+        let path = bidirectional_rev(&0, &4, small_graph, predecessors, |_, d, _| *d).unwrap();
+        assert_eq!(nodes(&path, 4), vec![0, 1, 3, 4]);
+        assert_eq!(path, vec![(3, 1), (1, 1), (0, 1)]);
+        let path = bidirectional_rev(&0, &4, small_graph, predecessors, |_, _, _| 1).unwrap();
+        assert_eq!(nodes(&path, 4), vec![0, 2, 4]);
+        assert_eq!(
+            bidirectional_rev(&0, &0, small_graph, predecessors, |_, _, _| 1),
+            Ok(vec![])
+        );
+        assert_eq!(
+            bidirectional_rev(&0, &5, small_graph, predecessors, |_, _, _| 1),
+            Err(NoPathFound)
+        );
+    }
+
+    #[test]
+    fn test_bidirectional_matches_bfs() {
+        let next = |&n: &i64| {
+            [(n + 1, '+'), (n * 2, '*')]
+                .into_iter()
+                .filter(|&(n, _)| n <= 300)
+        };
+        let prev = |&n: &i64| {
+            let half = (n % 2 == 0).then_some((n / 2, '*'));
+            [(n - 1, '+')]
+                .into_iter()
+                .chain(half)
+                .filter(|&(n, _)| n >= 1)
+        };
+        for goal in 1..=300 {
+            let (expected, _) = bfs_rev(&1, |n| *n == goal, next).unwrap();
+            let path = bidirectional_rev(&1, &goal, next, prev, |_, _, _| 1).unwrap();
+            assert_eq!(path.len(), expected.len(), "to {goal}");
+            let mut n = 1;
+            for (from, edge) in path.iter().rev() {
+                assert_eq!(*from, n);
+                n = if *edge == '+' { n + 1 } else { n * 2 };
+            }
+            assert_eq!(n, goal);
+        }
+    }
+
+    #[test]
+    fn test_ida_star() {
+        let ida = |goal, max_nodes| {
+            ida_star_rev_bounded(
+                &0,
+                |n| *n == goal,
+                small_graph,
+                |_| 0,
+                |_, d, _| *d,
+                max_nodes,
+            )
+        };
+        for max_nodes in [0, 2, 100] {
+            let (path, goal) = ida(4, max_nodes).unwrap();
+            assert_eq!(nodes(&path, goal), vec![0, 1, 3, 4]);
+            assert_eq!(ida(0, max_nodes), Ok((vec![], 0)));
+            assert_eq!(ida(5, max_nodes), Err(NoPathFound));
+        }
+    }
+
+    #[test]
+    fn test_ida_star_matches_bfs() {
+        // Both ways around a ring of 12 nodes, and jumps of 5 to the right.
+        let next = |&n: &i64| [((n + 1) % 12, 1), ((n + 11) % 12, -1), ((n + 5) % 12, 5)];
+        let ring_distance = |&n: &i64, goal: i64| {
+            let d = (n - goal).rem_euclid(12);
+            cmp::min(d, 12 - d)
+        };
+        for goal in 0..12 {
+            let (expected, _) = bfs_rev(&0, |n| *n == goal, next).unwrap();
+            for max_nodes in [0, 3, 12] {
+                let (path, found) = ida_star_rev_bounded(
+                    &0,
+                    |n| *n == goal,
+                    next,
+                    // A jump covers at most 5 steps around the ring.
+                    |n| (ring_distance(n, goal) + 4) / 5,
+                    |_, _, _| 1,
+                    max_nodes,
+                )
+                .unwrap();
+                assert_eq!(found, goal);
+                assert_eq!(path.len(), expected.len(), "to {goal} with {max_nodes}");
+                let end = path.iter().rev().fold(0, |n, (from, step)| {
+                    assert_eq!(*from, n);
+                    (n + step).rem_euclid(12)
+                });
+                assert_eq!(end, goal);
+            }
+        }
+        let (path, _) = ida_star_rev(&0, |n| *n == 6, next, |_| 0, |_, _, _| 1).unwrap();
+        assert_eq!(path.len(), 2);
+    }
+
+    #[test]
+    fn test_all_optimal_paths() {
+        let neighbors = |n: &usize| match n {
+            0 => vec![(1, 1), (2, 1), (5, 3)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 1), (4, 2)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        };
+        let all =
+            |goal: fn(&usize) -> bool| all_optimal_paths(&0, goal, neighbors, |_| 0, |_, d, _| *d);
+        let paths = all(|n| *n == 4 || *n == 5).unwrap();
+        assert_eq!(paths.cost(), 3);
+        assert_eq!(paths.count(), 4);
+        let mut goals: Vec<_> = paths.goals().copied().collect();
+        goals.sort();
+        assert_eq!(goals, vec![4, 5]);
+        let mut found: Vec<_> = paths
+            .paths_rev()
+            .map(|(path, goal)| nodes(&path, goal))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                vec![0, 1, 3, 4],
+                vec![0, 2, 3, 4],
+                vec![0, 2, 4],
+                vec![0, 5]
+            ]
+        );
+
+        let paths = all(|n| *n == 0).unwrap();
+        assert_eq!((paths.cost(), paths.count()), (0, 1));
+        assert_eq!(paths.paths_rev().collect::<Vec<_>>(), vec![(vec![], 0)]);
+        assert!(all(|n| *n == 6).is_err());
+    }
+
+    #[test]
+    fn test_k_shortest_paths_with_equal_edges() {
+        // Two ways around a diamond, with edges that are all the same.
+        let neighbors = |n: &u8| match n {
+            0 => vec![(1, ()), (2, ())],
+            1 | 2 => vec![(3, ())],
+            _ => vec![],
+        };
+        let distance = |&from: &u8, _: &(), _: &u8| if from == 2 { 2 } else { 1 };
+        let paths = k_shortest_paths_rev(&0, |n| *n == 3, neighbors, distance, 5);
+        assert_eq!(paths.iter().map(|p| p.cost).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(paths[1].path_rev, vec![(2, ()), (0, ())]);
+        let all = all_optimal_paths(&0, |n| *n == 3, neighbors, |_| 0, |_, _, _| 1).unwrap();
+        assert_eq!(all.count(), 2);
+        let paths = k_shortest_paths_rev(&0, |n| *n == 3, neighbors, |_, _, _| 1, 5);
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[test]
+    fn test_all_optimal_paths_on_grid() {
+        // Right or down from (0, 0) to (4, 3), which can be done in 7 choose 3 ways.
+        let paths = all_optimal_paths(
+            &(0, 0),
+            |&p| p == (4, 3),
+            |&(x, y)| [((x + 1, y), 'R'), ((x, y + 1), 'D')],
+            |&(x, y)| if x > 4 || y > 3 { i64::MAX } else { 7 - x - y },
+            |_, _, _| 1,
+        )
+        .unwrap();
+        assert_eq!(paths.count(), 35);
+        let moves: HashSet<String> = paths
+            .paths_rev()
+            .map(|(path, _)| path.iter().rev().map(|(_, m)| m).collect())
+            .collect();
+        assert_eq!(moves.len(), 35);
+        assert!(
+            moves
+                .iter()
+                .all(|m| m.matches('R').count() == 4 && m.len() == 7)
+        );
+    }
+
+    #[test]
+    fn test_k_shortest_paths() {
+        let neighbors = |n: &char| match n {
+            'C' => vec![('D', 3), ('E', 2)],
+            'D' => vec![('F', 4)],
+            'E' => vec![('D', 1), ('F', 2), ('G', 3)],
+            'F' => vec![('G', 2), ('H', 1)],
+            'G' => vec![('H', 2)],
+            _ => vec![],
+        };
+        let k_shortest = |k| k_shortest_paths_rev(&'C', |n| *n == 'H', neighbors, |_, d, _| *d, k);
+        let route = |path: &RankedPath<char, i64>| -> String {
+            path.path_rev
+                .iter()
+                .rev()
+                .map(|(n, _)| n)
+                .chain([&path.goal])
+                .collect()
+        };
+        let paths = k_shortest(3);
+        assert_eq!(
+            paths.iter().map(|p| p.cost).collect::<Vec<_>>(),
+            vec![5, 7, 8]
+        );
+        assert_eq!(route(&paths[0]), "CEFH");
+        assert_eq!(route(&paths[1]), "CEGH");
+
+        let paths = k_shortest(10);
+        assert_eq!(
+            paths.iter().map(|p| p.cost).collect::<Vec<_>>(),
+            vec![5, 7, 8, 8, 8, 11, 11]
+        );
+        let routes: HashSet<_> = paths.iter().map(route).collect();
+        assert_eq!(routes.len(), 7);
+        assert!(k_shortest(0).is_empty());
+        assert!(k_shortest_paths_rev(&'H', |n| *n == 'C', neighbors, |_, d, _| *d, 2).is_empty());
+    }
+
+    #[test]
+    fn test_check_heuristic() {
+        let check = |h: [i64; 5], max_nodes| {
+            check_heuristic(
+                &0,
+                |n| *n == 4,
+                small_graph,
+                |&n| h[n],
+                |_, d, _| *d,
+                max_nodes,
+            )
+        };
+        assert_eq!(check([0; 5], 10), vec![]);
+        assert_eq!(check([3, 2, 1, 1, 0], 10), vec![]);
+        // Admissible, but it drops by 3 over an edge of 1.
+        assert_eq!(
+            check([3, 0, 1, 1, 0], 10),
+            vec![HeuristicError::Inconsistent {
+                from: 0,
+                edge: 1,
+                to: 1,
+                h_from: 3,
+                h_to: 0,
+                distance: 1
+            }]
+        );
+        let errors = check([3, 2, 5, 1, 0], 10);
+        assert_eq!(
+            errors[0],
+            HeuristicError::Overestimate {
+                node: 2,
+                h: 5,
+                actual: 1
+            }
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "h(2) = 5, but it is 1 away from the goal"
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "h(2) = 5, but 1 is 1 long and leads to 4 with h = 0"
+        );
+        assert_eq!(errors.len(), 2);
+        // The goal isn't reached, so nothing can be said about the heuristic but its drops.
+        assert_eq!(check([3, 2, 5, 1, 0], 2), vec![]);
+        assert_eq!(check([i64::MAX; 5], 10).len(), 5);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let distances = dijkstra(&0, small_graph, |_, d, _| *d);
+        assert_eq!(distances.get(&4), Some(3));
+        assert_eq!(distances.get(&2), Some(5));
+        assert_eq!(distances.get(&5), None);
+        assert_eq!(nodes(&distances.path_rev(&4).unwrap(), 4), vec![0, 1, 3, 4]);
+        assert_eq!(
+            distances.iter().map(|(n, d)| (*n, d)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 5), (3, 2), (4, 3)]
+        );
+        assert_eq!(distances.len(), 5);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let (path, goal) = dfs_rev(&0, |n| *n == 4, small_graph).unwrap();
+        assert_eq!(nodes(&path, goal), vec![0, 1, 3, 4]);
+        assert_eq!(dfs_rev(&1, |n| *n == 2, small_graph), Err(NoPathFound));
+        assert_eq!(flood_fill(&0, small_graph), vec![0, 1, 3, 4, 2]);
+        assert_eq!(flood_fill(&5, small_graph), vec![5]);
+    }
+
+    /// Searches the numbers for `goal` by adding one or doubling, so it never runs out of nodes.
+    fn count_to(goal: i64, limits: &Limits) -> Result<Found<i64, char>, SearchError<i64, char>> {
+        a_star_rev_limited(
+            &1,
+            |n| *n == goal,
+            |&n| [(n + 1, '+'), (n * 2, '*')],
+            |&n| {
+                if n > goal { i64::MAX } else { goal - n }
+            },
+            |_, _, _| 1,
+            |_, _| (),
+            limits,
+            &mut (),
+        )
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut trace = Trace::default();
+        a_star_rev_limited(
+            &1,
+            |n| *n == 4,
+            |&n| [(n + 1, '+'), (n * 2, '*')],
+            |&n| if n > 4 { i64::MAX } else { 4 - n },
+            |_, _, _| 1,
+            |_, _| (),
+            &Limits::default(),
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(
+            trace.steps,
+            vec![
+                Step {
+                    node: 1,
+                    g: 0,
+                    h: 3,
+                    parent: None
+                },
+                Step {
+                    node: 2,
+                    g: 1,
+                    h: 2,
+                    parent: Some((1, '+'))
+                },
+                Step {
+                    node: 4,
+                    g: 2,
+                    h: 0,
+                    parent: Some((2, '*'))
+                },
+            ]
+        );
+        assert_eq!(
+            trace.to_dot(),
+            "digraph search {\n    node [shape=box];\n    \
+             n0 [label=\"#0 1\\ng=0 h=3\"];\n    \
+             n1 [label=\"#1 2\\ng=1 h=2\"];\n    \
+             n0 -> n1 [label=\"'+'\"];\n    \
+             n2 [label=\"#2 4\\ng=2 h=0\"];\n    \
+             n1 -> n2 [label=\"'*'\"];\n\
+             }\n"
+        );
+        let lines: Vec<_> = trace.to_json_lines().lines().map(String::from).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[2],
+            r#"{"edge":"'*'","g":2,"h":0,"node":"4","parent":"2","step":2}"#
+        );
+    }
+
+    #[test]
+    fn test_trace_escapes_labels() {
+        let mut trace = Trace::<&str, ()>::default();
+        trace.expanded(&"say \"hi\"", 0, i64::MAX, None);
+        assert!(
+            trace
+                .to_dot()
+                .contains(r##"[label="#0 \"say \\\"hi\\\"\"\ng=0 h=inf"]"##)
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let found = count_to(10, &Limits::default()).unwrap();
+        assert_eq!(found.goal, 10);
+        assert!(found.stats.expanded as usize >= found.path_rev.len());
+        assert!(found.stats.max_open > 0);
+    }
+
+    #[test]
+    fn test_limits() {
+        let e = count_to(
+            1000,
+            &Limits {
+                max_expansions: Some(5),
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "search stopped, too many expansions, after expanding 5 nodes"
+        );
+        // Doubling gets closest the fastest.
+        let SearchError::Stopped { best, path_rev, .. } = e else {
+            panic!("{e:?}");
+        };
+        assert_eq!(best, 16);
+        assert_eq!(path_rev, vec![(8, '*'), (4, '*'), (2, '*'), (1, '+')]);
+
+        let e = count_to(
+            -1,
+            &Limits {
+                max_nodes: Some(100),
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_matches!(
+            e,
+            SearchError::Stopped {
+                reason: Stop::Nodes,
+                ..
+            }
+        );
+
+        let e = count_to(
+            -1,
+            &Limits {
+                deadline: Some(Instant::now()),
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_matches!(
+            e,
+            SearchError::Stopped {
+                reason: Stop::Deadline,
+                best: 1,
+                ..
+            }
+        );
+
+        let cancel = AtomicBool::new(true);
+        let e = count_to(
+            -1,
+            &Limits {
+                cancel: Some(&cancel),
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_matches!(
+            e,
+            SearchError::Stopped {
+                reason: Stop::Cancelled,
+                ..
+            }
+        );
+        assert_eq!(e.stats().expanded, 0);
+    }
 }
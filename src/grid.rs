@@ -0,0 +1,400 @@
+use crate::error::Expected;
+use std::fmt;
+use std::fmt::Formatter;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// A position in a [Grid], as `[x, y]`. `[0, 0]` is the top left corner.
+pub type Pos = [usize; 2];
+
+const NEIGHBORS_4: [[isize; 2]; 4] = [[0, -1], [-1, 0], [1, 0], [0, 1]];
+
+const NEIGHBORS_8: [[isize; 2]; 8] = [
+    [-1, -1],
+    [0, -1],
+    [1, -1],
+    [-1, 0],
+    [1, 0],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A cell that can be read from and written as a single character, like the lights in day 18.
+pub trait Tile: Sized {
+    /// What the parser expects to see, for error messages.
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl Tile for bool {
+    const EXPECTED: &'static str = "`#` or `.`";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Panics if there aren't exactly `width * height` cells.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "wrong number of cells");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| [x, y]))
+            .map(&mut f)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Reads a character map with one row per line. Surrounding whitespace and blank lines are
+    /// ignored, so that maps can be indented in tests.
+    pub fn parse_with(
+        s: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, Expected> {
+        let mut grid = Grid {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        };
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let mut width = 0;
+            for (i, c) in line.char_indices() {
+                if grid.height > 0 && width == grid.width {
+                    return Err(Expected::new(i, "the end of the line").within(s, line));
+                }
+                let cell = cell(c).ok_or_else(|| Expected::new(i, expected).within(s, line))?;
+                grid.cells.push(cell);
+                width += 1;
+            }
+            if grid.height == 0 {
+                grid.width = width;
+            } else if width < grid.width {
+                let what = format!("{} cells", grid.width);
+                return Err(Expected::end(line, what).within(s, line));
+            }
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn idx(&self, [x, y]: Pos) -> usize {
+        y * self.width + x
+    }
+
+    pub fn contains(&self, [x, y]: Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[self.idx(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            let i = self.idx(pos);
+            Some(&mut self.cells[i])
+        } else {
+            None
+        }
+    }
+
+    /// All cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| [x, y]))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn offset(&self, [x, y]: Pos, [dx, dy]: [isize; 2]) -> Option<Pos> {
+        let pos = [x.checked_add_signed(dx)?, y.checked_add_signed(dy)?];
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The positions above, left, right and below `pos`, if they are inside the grid.
+    pub fn neighbors_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS_4
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    /// Like [Grid::neighbors_4], but with the diagonals.
+    pub fn neighbors_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS_8
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The cells in column `x`, top to bottom. Panics if there is no such column, like [Grid::row].
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The cells in the rectangle between the two corners, including both, row by row. Empty if
+    /// the first corner is right of or below the second. Panics if the rectangle isn't empty and
+    /// doesn't fit into the grid.
+    pub fn rect(&self, [x0, y0]: Pos, [x1, y1]: Pos) -> impl Iterator<Item = &T> {
+        let rows = self.rect_rows([x0, y0], [x1, y1]);
+        self.cells
+            .chunks(self.width.max(1))
+            .skip(y0)
+            .take(rows)
+            .flat_map(move |row| &row[x0..=x1])
+    }
+
+    pub fn rect_mut(&mut self, [x0, y0]: Pos, [x1, y1]: Pos) -> impl Iterator<Item = &mut T> {
+        let rows = self.rect_rows([x0, y0], [x1, y1]);
+        self.cells
+            .chunks_mut(self.width.max(1))
+            .skip(y0)
+            .take(rows)
+            .flat_map(move |row| &mut row[x0..=x1])
+    }
+
+    /// How many rows [Grid::rect] goes through.
+    fn rect_rows(&self, [x0, y0]: Pos, [x1, y1]: Pos) -> usize {
+        if x0 > x1 || y0 > y1 {
+            return 0;
+        }
+        assert!(self.contains([x1, y1]), "rectangle out of bounds");
+        y1 - y0 + 1
+    }
+
+    /// Writes the grid with one line per row, ending in a newline.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            s.extend(self.row(y).iter().map(&mut cell));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// All positions that can be reached from `start` through horizontally or vertically
+    /// adjacent cells that are equal to the one at `start`, in the order they were found.
+    pub fn region(&self, start: Pos) -> Vec<Pos> {
+        let Some(value) = self.get(start) else {
+            return Vec::new();
+        };
+        let mut seen = Grid::new(self.width, self.height, false);
+        seen[start] = true;
+        let mut region = vec![start];
+        let mut i = 0;
+        while let Some(&pos) = region.get(i) {
+            for next in self.neighbors_4(pos) {
+                if !seen[next] && self[next] == *value {
+                    seen[next] = true;
+                    region.push(next);
+                }
+            }
+            i += 1;
+        }
+        region
+    }
+}
+
+impl<T: PartialEq + Clone> Grid<T> {
+    /// Sets the whole [Grid::region] around `start` to `value`, like the paint bucket in an image
+    /// editor. Returns how many cells were changed.
+    pub fn fill_region(&mut self, start: Pos, value: T) -> usize {
+        let region = self.region(start);
+        for &pos in &region {
+            self[pos] = value.clone();
+        }
+        region.len()
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        assert!(self.contains(pos), "{pos:?} is out of bounds");
+        &self.cells[self.idx(pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        assert!(self.contains(pos), "{pos:?} is out of bounds");
+        let i = self.idx(pos);
+        &mut self.cells[i]
+    }
+}
+
+impl<T: Tile> FromStr for Grid<T> {
+    type Err = Expected;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, T::EXPECTED, T::from_char)
+    }
+}
+
+impl<T: Tile> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(T::to_char))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render() {
+        let grid: Grid<bool> = "
+            #.#.
+            .##.
+        "
+        .parse()
+        .unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 2));
+        assert!(grid[[2, 0]] && !grid[[3, 1]]);
+        assert_eq!(grid.to_string(), "#.#.\n.##.\n");
+        assert_eq!(
+            "#.#\n.x.".parse::<Grid<bool>>(),
+            Err(Expected::new(5, "`#` or `.`"))
+        );
+        assert_eq!(
+            "#.#\n.#\n".parse::<Grid<bool>>(),
+            Err(Expected::new(6, "3 cells"))
+        );
+        assert_eq!(
+            "#.#\n.#..\n".parse::<Grid<bool>>(),
+            Err(Expected::new(7, "the end of the line"))
+        );
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = Grid::from_fn(3, 2, |[x, y]| x + 10 * y);
+        assert_eq!(grid.get([2, 1]), Some(&12));
+        assert_eq!(grid.get([3, 0]), None);
+        assert_eq!(grid.get([0, 2]), None);
+        assert_eq!(grid.positions().collect::<Vec<_>>()[4], [1, 1]);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbors_4([1, 1]).count(), 4);
+        assert_eq!(grid.neighbors_8([1, 1]).count(), 8);
+        assert_eq!(
+            grid.neighbors_4([0, 0]).collect::<Vec<_>>(),
+            vec![[1, 0], [0, 1]]
+        );
+        assert_eq!(grid.neighbors_8([2, 2]).count(), 3);
+    }
+
+    #[test]
+    fn test_views() {
+        let mut grid = Grid::from_fn(4, 3, |[x, y]| x + 10 * y);
+        assert_eq!(grid.row(1), &[10, 11, 12, 13]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 12, 22]);
+        assert!(std::panic::catch_unwind(|| grid.column(4).count()).is_err());
+        assert_eq!(
+            grid.rect([1, 1], [2, 2]).copied().collect::<Vec<_>>(),
+            vec![11, 12, 21, 22]
+        );
+        assert_eq!(grid.rect([2, 0], [1, 2]).count(), 0);
+        assert_eq!(grid.rect([0, 2], [3, 1]).count(), 0);
+        assert_eq!(Grid::new(0, 3, 0).rect([1, 0], [0, 2]).count(), 0);
+        grid.rect_mut([0, 0], [1, 1]).for_each(|c| *c = 0);
+        grid.row_mut(2)[3] = 99;
+        assert_eq!(grid.iter().filter(|&&c| c == 0).count(), 4);
+        assert_eq!(grid[[3, 2]], 99);
+    }
+
+    #[test]
+    fn test_region() {
+        let mut grid: Grid<bool> = "
+            ##..
+            #..#
+            .#.#
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(grid.region([0, 0]).len(), 3);
+        assert_eq!(grid.region([2, 0]).len(), 5);
+        assert_eq!(grid.region([3, 1]).len(), 2);
+        assert_eq!(grid.fill_region([2, 0], true), 5);
+        assert_eq!(grid.to_string(), "####\n####\n.###\n");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::error::{Expected, ParseError};
+use crate::solution::Day;
+use anyhow::{Context, bail};
+use std::io::Read;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+/// The text of a puzzle input, along with the directory of the day it belongs to, so that days
+/// which need more than one file (like the shop in day 21) can read the rest from there.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PuzzleInput {
+    text: String,
+    dir: PathBuf,
+    /// Where the text came from, for error messages.
+    name: String,
+}
+
+impl PuzzleInput {
+    pub fn new(text: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        PuzzleInput {
+            text: text.into(),
+            dir: dir.into(),
+            name: "<input>".to_string(),
+        }
+    }
+
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        PuzzleInput {
+            name: name.into(),
+            ..self
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Reads another file from the day's directory.
+    pub fn companion(&self, name: &str) -> anyhow::Result<PuzzleInput> {
+        let path = self.dir.join(name);
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Ok(PuzzleInput::new(text, &self.dir).with_name(path.display().to_string()))
+    }
+
+    /// Locates an error from parsing the whole text.
+    pub fn error(&self, expected: Expected) -> ParseError {
+        ParseError::new(&self.name, &self.text, expected)
+    }
+
+    pub fn parse_with<T>(
+        &self,
+        parse: impl FnOnce(&str) -> Result<T, Expected>,
+    ) -> Result<T, ParseError> {
+        parse(&self.text).map_err(|e| self.error(e))
+    }
+
+    /// Parses every line on its own.
+    pub fn parse_lines<T: FromStr<Err = Expected>>(&self) -> Result<Vec<T>, ParseError> {
+        self.parse_lines_with(str::parse)
+    }
+
+    pub fn parse_lines_with<T>(
+        &self,
+        mut parse: impl FnMut(&str) -> Result<T, Expected>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.text
+            .lines()
+            .map(|line| parse(line).map_err(|e| self.error(e.within(&self.text, line))))
+            .collect()
+    }
+}
+
+impl Deref for PuzzleInput {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+/// Where to read a day's puzzle input from.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Source {
+    /// The day's own input file in its directory, e.g. `d07/input.txt`.
+    #[default]
+    Default,
+    File(PathBuf),
+    Stdin,
+}
+
+impl FromStr for Source {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => bail!("expected a path or -"),
+            "-" => Ok(Source::Stdin),
+            path => Ok(Source::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// The directory that holds one `dNN` directory per day.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Inputs {
+    pub root: PathBuf,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Inputs {
+            root: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+        }
+    }
+}
+
+impl Inputs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Inputs { root: root.into() }
+    }
+
+    pub fn day_dir(&self, day: u8) -> PathBuf {
+        self.root.join(format!("d{day:02}"))
+    }
+
+    /// The expected answers that belong to these inputs.
+    pub fn answers_file(&self) -> PathBuf {
+        self.root.join("answers.json")
+    }
+
+    pub fn load(&self, day: &Day, source: &Source) -> anyhow::Result<PuzzleInput> {
+        let dir = self.day_dir(day.day);
+        let read = |path: &Path| {
+            fs::read_to_string(path)
+                .map(|text| (text, path.display().to_string()))
+                .with_context(|| format!("failed to read {}", path.display()))
+        };
+        let (text, name) = match source {
+            Source::Default => read(&dir.join(day.input_file))?,
+            Source::File(path) => read(path)?,
+            Source::Stdin => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .context("failed to read stdin")?;
+                (text, "<stdin>".to_string())
+            }
+        };
+        Ok(PuzzleInput::new(text, dir).with_name(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!("-".parse::<Source>().unwrap(), Source::Stdin);
+        assert_eq!(
+            "d07/input.txt".parse::<Source>().unwrap(),
+            Source::File(PathBuf::from("d07/input.txt"))
+        );
+        assert!("".parse::<Source>().is_err());
+    }
+
+    #[test]
+    fn test_load_default() {
+        let inputs = Inputs::default();
+        let input = inputs
+            .load(days::get(23).unwrap(), &Source::Default)
+            .unwrap();
+        assert_eq!(input.dir(), inputs.day_dir(23));
+        assert!(input.name().ends_with("input.asm"));
+        assert!(input.lines().all(|l| l.starts_with(['h', 't', 'i', 'j'])));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = PuzzleInput::new("12\n 7\n3x\n", "").with_name("numbers.txt");
+        let parse = |line: &str| crate::error::number::<i32>(line, line.trim_start());
+        assert_eq!(
+            input.parse_lines_with(parse),
+            Err(ParseError {
+                file: "numbers.txt".to_string(),
+                line: 3,
+                column: 1,
+                text: "3x".to_string(),
+                expected: "expected a number".to_string(),
+            })
+        );
+        let input = PuzzleInput::new("12\n 7\n", "");
+        assert_eq!(input.parse_lines_with(parse), Ok(vec![12, 7]));
+    }
+
+    #[test]
+    fn test_load_missing() {
+        let inputs = Inputs::new("does-not-exist");
+        assert!(
+            inputs
+                .load(days::get(1).unwrap(), &Source::Default)
+                .is_err()
+        );
+    }
+}
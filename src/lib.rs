@@ -1,12 +1,45 @@
+#![feature(iter_map_windows)]
+#![cfg_attr(test, feature(test))]
+
+#[cfg(test)]
+extern crate test;
+
+pub mod answers;
+pub mod bench;
+pub mod days;
+pub mod error;
+pub mod examples;
+pub mod generate;
 pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod logging;
+pub mod output;
+pub mod parse;
+pub mod progress;
+pub mod repl;
+pub mod runner;
+pub mod solution;
+pub mod watch;
 
 use std::collections::HashMap;
 use std::{cmp, fmt};
 
 pub fn tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
+    tsp_route(n, dist).0
+}
+
+pub fn inv_tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
+    -tsp(n, |a, b| -dist(a, b))
+}
+
+/// Like [tsp], but also returns the round trip itself. It starts at node 0, and the way back to
+/// node 0 at the end is left out.
+pub fn tsp_route(n: u16, dist: impl Fn(u16, u16) -> i32) -> (i32, Vec<u16>) {
+    // (visited set, last node) -> (cost, node before the last one)
     let mut g = HashMap::new();
     for k in 0..n {
-        g.insert((1u64 << k, k), dist(0, k));
+        g.insert((1u64 << k, k), (dist(0, k), 0));
     }
 
     for s in 2..=n - 1 {
@@ -15,10 +48,11 @@ pub fn tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
             if sub.count_ones() as u16 == s {
                 for k in 0..n {
                     if ((1 << k) & sub) != 0 {
-                        let mut result = i32::MAX;
+                        let mut result = (i32::MAX, 0);
                         for m in 0..n {
                             if m != k && ((1 << m) & sub) != 0 {
-                                result = cmp::min(result, g[&(sub & !(1 << k), m)] + dist(m, k));
+                                let cost = g[&(sub & !(1 << k), m)].0 + dist(m, k);
+                                result = cmp::min(result, (cost, m));
                             }
                         }
                         g.insert((sub, k), result);
@@ -28,14 +62,28 @@ pub fn tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
         }
     }
 
-    (1..n)
-        .map(|k| g[&(((1u64 << n) - 1) & !1u64, k)] + dist(k, 0))
+    let all = ((1u64 << n) - 1) & !1u64;
+    let (cost, last) = (1..n)
+        .map(|k| (g[&(all, k)].0 + dist(k, 0), k))
         .min()
-        .unwrap()
+        .unwrap();
+
+    let mut route = Vec::with_capacity(n as usize);
+    let (mut sub, mut k) = (all, last);
+    while sub != 0 {
+        route.push(k);
+        let (_, prev) = g[&(sub, k)];
+        sub &= !(1 << k);
+        k = prev;
+    }
+    route.push(0);
+    route.reverse();
+    (cost, route)
 }
 
-pub fn inv_tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
-    -tsp(n, |a, b| -dist(a, b))
+pub fn inv_tsp_route(n: u16, dist: impl Fn(u16, u16) -> i32) -> (i32, Vec<u16>) {
+    let (cost, route) = tsp_route(n, |a, b| -dist(a, b));
+    (-cost, route)
 }
 
 pub struct CompositionsGenerator<I> {
@@ -125,6 +173,28 @@ mod tests {
     use itertools::Itertools;
     use std::collections::HashSet;
 
+    #[test]
+    fn test_tsp_route() {
+        let dist = |a: u16, b: u16| {
+            [
+                // 0  1  2  3
+                [0, 1, 9, 1], // 0
+                [1, 0, 1, 9], // 1
+                [9, 1, 0, 1], // 2
+                [1, 9, 1, 0], // 3
+            ][a as usize][b as usize]
+        };
+        let (cost, route) = tsp_route(4, dist);
+        assert_eq!(cost, 4);
+        assert!(route == [0, 1, 2, 3] || route == [0, 3, 2, 1]);
+        assert_eq!(tsp(4, dist), 4);
+
+        let (cost, route) = inv_tsp_route(4, dist);
+        assert_eq!(cost, 20);
+        assert_eq!(route.len(), 4);
+        assert_eq!(route[0], 0);
+    }
+
     #[test]
     fn test_compositions() {
         fn direct_compositions(n: u32, total: u32) -> Vec<Vec<u32>> {
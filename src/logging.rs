@@ -0,0 +1,174 @@
+use anyhow::{Context, bail};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Instant;
+use std::{cmp, io};
+
+/// Which log messages to show, written like `info,d07=trace,graph=debug`: a default level for
+/// everything, and levels for single targets. A target is a day like `d07`, or a module of the
+/// library like `graph`, and also covers everything inside it, like `d07::grammar`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Filter {
+    pub default: LevelFilter,
+    pub targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            default: LevelFilter::Warn,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = level
+                        .parse()
+                        .with_context(|| format!("invalid log level in {directive}"))?;
+                    if target.is_empty() {
+                        bail!("missing target in {directive}");
+                    }
+                    filter.targets.push((target.to_string(), level));
+                }
+                None => match directive.parse() {
+                    Ok(level) => filter.default = level,
+                    Err(_) => filter
+                        .targets
+                        .push((directive.to_string(), LevelFilter::Trace)),
+                },
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    /// Makes the default level more verbose by `steps`, as in `-vv`.
+    pub fn verbose(self, steps: u8) -> Self {
+        let levels = LevelFilter::iter().collect::<Vec<_>>();
+        let i = levels.iter().position(|&l| l == self.default).unwrap_or(0);
+        Filter {
+            default: levels[cmp::min(i + steps as usize, levels.len() - 1)],
+            ..self
+        }
+    }
+
+    /// The level for `target`, from the most specific directive that covers it.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        let target = short_target(target);
+        self.targets
+            .iter()
+            .filter(|(t, _)| {
+                target == t
+                    || target
+                        .strip_prefix(t.as_str())
+                        .is_some_and(|r| r.starts_with("::"))
+            })
+            .max_by_key(|(t, _)| t.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, cmp::max)
+    }
+}
+
+/// Drops the crate name from a module path, and the `days` module that every day lives in, so
+/// that `aoc2015::days::d07::grammar` becomes `d07::grammar`.
+pub fn short_target(target: &str) -> &str {
+    let target = target.strip_prefix("aoc2015::").unwrap_or(target);
+    target.strip_prefix("days::").unwrap_or(target)
+}
+
+/// Writes to stderr, so that it doesn't get mixed up with the answers.
+struct Logger {
+    filter: Filter,
+    start: Instant,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let _ = writeln!(
+            io::stderr().lock(),
+            "{:>9.3?} {:<5} {}: {}",
+            self.start.elapsed(),
+            record.level(),
+            short_target(record.target()),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {
+        let _ = io::stderr().flush();
+    }
+}
+
+/// Installs the logger. Fails if there already is one.
+pub fn init(filter: Filter) -> anyhow::Result<()> {
+    log::set_max_level(filter.max_level());
+    log::set_boxed_logger(Box::new(Logger {
+        filter,
+        start: Instant::now(),
+    }))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        let filter: Filter = "info, d07=trace,graph=debug,d22".parse().unwrap();
+        assert_eq!(filter.default, LevelFilter::Info);
+        assert_eq!(
+            filter.targets,
+            vec![
+                ("d07".to_string(), LevelFilter::Trace),
+                ("graph".to_string(), LevelFilter::Debug),
+                ("d22".to_string(), LevelFilter::Trace),
+            ]
+        );
+        assert!("d07=loud".parse::<Filter>().is_err());
+        assert!("=debug".parse::<Filter>().is_err());
+        assert_eq!("".parse::<Filter>().unwrap(), Filter::default());
+    }
+
+    #[test]
+    fn test_level() {
+        let filter: Filter = "d07=trace,d07::grammar=off".parse().unwrap();
+        assert_eq!(filter.level("aoc2015::days::d07"), LevelFilter::Trace);
+        assert_eq!(
+            filter.level("aoc2015::days::d07::grammar"),
+            LevelFilter::Off
+        );
+        assert_eq!(filter.level("aoc2015::days::d07x"), LevelFilter::Warn);
+        assert_eq!(filter.level("aoc2015::graph"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_verbose() {
+        assert_eq!(Filter::default().verbose(1).default, LevelFilter::Info);
+        assert_eq!(Filter::default().verbose(9).default, LevelFilter::Trace);
+    }
+}
@@ -0,0 +1,284 @@
+use anyhow::{Context, bail};
+use aoc2015::answers::{Registry, Verdict};
+use aoc2015::bench::{Report, bench};
+use aoc2015::days;
+use aoc2015::days::Selection;
+use aoc2015::generate;
+use aoc2015::input::{Inputs, Source};
+use aoc2015::logging::Filter;
+use aoc2015::output::DayOutput;
+use aoc2015::runner::{DayResult, Summary, default_jobs, run_days};
+use aoc2015::solution::Day;
+use aoc2015::watch;
+use aoc2015::watch::Watcher;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs the Advent of Code 2015 solutions.
+#[derive(Parser, Debug)]
+#[command(name = "aoc", args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Which days to run: a single day (`7`), a range (`1..=25`, `3..7`) or `all`.
+    #[arg(required = true)]
+    days: Option<Selection>,
+
+    /// Read the puzzle input from this file instead, or from stdin if it is `-`. Only works
+    /// when running a single day.
+    #[arg(short, long, value_name = "PATH")]
+    input: Option<Source>,
+
+    /// Directory with one `dNN` directory per day, holding the default inputs.
+    #[arg(long, value_name = "DIR", env = "AOC_INPUT_DIR")]
+    input_dir: Option<PathBuf>,
+
+    /// How many days to run at the same time. Defaults to the number of CPUs.
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Log more, once for info and twice for debug messages, and so on.
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Which log messages to show, like `info,d07=trace,graph=debug`. Days are `d01` to `d25`.
+    #[arg(long, value_name = "FILTER", env = "AOC_LOG", default_value = "warn")]
+    log: Filter,
+
+    /// Check the answers against the expected ones, and fail if any of them don't match.
+    #[arg(long, conflicts_with = "bench")]
+    verify: bool,
+
+    /// Print one JSON document per day with the answers, timings and anything else the day
+    /// found along the way. Fails if any day does.
+    #[arg(long, conflicts_with_all = ["bench", "verify"])]
+    json: bool,
+
+    /// Solve the day again whenever its input or examples change, and show which answers changed.
+    /// Only works when running a single day.
+    #[arg(long, conflicts_with_all = ["bench", "verify", "json"])]
+    watch: bool,
+
+    /// File with the expected answers for `--verify`. Defaults to `answers.json` in the input
+    /// directory.
+    #[arg(long, value_name = "PATH")]
+    answers: Option<PathBuf>,
+
+    /// Solve every day this many times and report how long parsing and each part took.
+    #[arg(long, value_name = "RUNS")]
+    bench: Option<usize>,
+
+    /// Write the benchmark report to this file as JSON.
+    #[arg(long, value_name = "PATH", requires = "bench")]
+    bench_out: Option<PathBuf>,
+
+    /// Compare the benchmark against a report saved with `--bench-out`, and fail if any median
+    /// got slower by more than `--threshold`.
+    #[arg(long, value_name = "PATH", requires = "bench")]
+    baseline: Option<PathBuf>,
+
+    /// How much slower than the baseline counts as a regression, in percent.
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 10.0,
+        requires = "baseline"
+    )]
+    threshold: f64,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a random puzzle input for a day, for stress testing.
+    Generate {
+        day: u8,
+
+        /// The same seed always gives the same input.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// How big the input should be. What that means depends on the day, like the number of
+        /// lines or of wires.
+        #[arg(long)]
+        size: Option<usize>,
+    },
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let args = Args::parse();
+    aoc2015::logging::init(args.log.clone().verbose(args.verbose))?;
+    if let Some(Command::Generate { day, seed, size }) = args.command {
+        let generator =
+            generate::get(day).with_context(|| format!("no generator for day {day}"))?;
+        print!("{}", generator.generate(seed, size));
+        return Ok(ExitCode::SUCCESS);
+    }
+    let selection = args.days.as_ref().context("no days given")?;
+    // Bars of days running in parallel would draw over each other.
+    aoc2015::progress::enable_terminal(selection.0.len() == 1 && !args.json);
+    if args.input.is_some() && selection.0.len() != 1 {
+        bail!("--input only works when running a single day");
+    }
+    if args.watch && selection.0.len() != 1 {
+        bail!("--watch only works when running a single day");
+    }
+    let inputs = args.input_dir.clone().map(Inputs::new).unwrap_or_default();
+    let days = selection
+        .0
+        .iter()
+        .map(|&day| days::get(day).with_context(|| format!("day {day} is not solved")))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if args.watch {
+        watch(&args, &inputs, days[0])
+    } else if let Some(runs) = args.bench {
+        run_bench(&args, &inputs, &days, runs)
+    } else if args.verify {
+        verify(&args, &inputs, &days)
+    } else if args.json {
+        print_json(&args, &inputs, &days)
+    } else {
+        print_answers(&args, &inputs, &days)
+    }
+}
+
+fn source(args: &Args) -> Source {
+    args.input.clone().unwrap_or_default()
+}
+
+/// Solves all `days` in parallel, see `--jobs`.
+fn run(args: &Args, inputs: &Inputs, days: &[&Day]) -> Vec<DayResult> {
+    let source = source(args);
+    run_days(days, args.jobs.unwrap_or_else(default_jobs), |day| {
+        inputs.load(day, &source).and_then(|input| day.run(&input))
+    })
+}
+
+fn print_answers(args: &Args, inputs: &Inputs, days: &[&Day]) -> anyhow::Result<ExitCode> {
+    let before = Instant::now();
+    let mut results = run(args, inputs, days);
+    if results.len() == 1 {
+        let DayResult { day, result, .. } = results.remove(0);
+        let (answers, _) = result.with_context(|| format!("day {day} failed"))?;
+        println!("Day {day:02}");
+        println!("Part1: {}", answers.part1);
+        println!("Part2: {}", answers.part2);
+        return Ok(ExitCode::SUCCESS);
+    }
+    let summary = Summary {
+        results: &results,
+        wall_time: before.elapsed(),
+    };
+    println!("{summary}");
+    Ok(if summary.failed() > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn print_json(args: &Args, inputs: &Inputs, days: &[&Day]) -> anyhow::Result<ExitCode> {
+    let mut failed = false;
+    for DayResult { day, result, .. } in run(args, inputs, days) {
+        let output = DayOutput::new(day, result);
+        failed |= output.is_failed();
+        println!("{}", serde_json::to_string(&output)?);
+    }
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn verify(args: &Args, inputs: &Inputs, days: &[&Day]) -> anyhow::Result<ExitCode> {
+    let registry = Registry::load(args.answers.as_ref().unwrap_or(&inputs.answers_file()))?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for DayResult { day, result, .. } in run(args, inputs, days) {
+        let verdicts = match result {
+            Ok((answers, _)) => registry.verify(day, &answers),
+            Err(e) => {
+                println!("Day {day:02}: FAIL, {e:#}");
+                failed += 2;
+                continue;
+            }
+        };
+        for (part, verdict) in verdicts.iter().enumerate() {
+            println!("Day {day:02} part {}: {verdict}", part + 1);
+            match verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { .. } => failed += 1,
+                Verdict::Missing => missing += 1,
+            }
+        }
+    }
+    println!();
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    Ok(if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// How often `--watch` looks for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+fn watch(args: &Args, inputs: &Inputs, day: &Day) -> anyhow::Result<ExitCode> {
+    let source = source(args);
+    let files = match &source {
+        Source::Default => Vec::new(),
+        Source::File(path) => vec![path.clone()],
+        Source::Stdin => bail!("--watch can't watch stdin"),
+    };
+    let dirs = vec![inputs.day_dir(day.day), inputs.examples_dir(day.day)];
+    let mut watcher = Watcher::new(dirs, files);
+    let mut previous = None;
+    loop {
+        println!("Day {:02}", day.day);
+        let outcome = watch::solve(day, inputs, &source);
+        print!("{}", watch::diff(previous.as_ref(), &outcome));
+        previous = Some(outcome);
+        println!("Watching for changes...");
+        let changes = loop {
+            thread::sleep(POLL_INTERVAL);
+            let changes = watcher.changes();
+            if !changes.is_empty() {
+                break changes;
+            }
+        };
+        println!();
+        for path in changes {
+            println!("Changed: {}", path.display());
+        }
+    }
+}
+
+fn run_bench(args: &Args, inputs: &Inputs, days: &[&Day], runs: usize) -> anyhow::Result<ExitCode> {
+    let mut report = Report::default();
+    for day in days {
+        let input = inputs.load(day, &source(args))?;
+        let result = bench(day, &input, runs).with_context(|| format!("day {} failed", day.day))?;
+        report.0.push(result);
+    }
+    print!("{report}");
+    if let Some(path) = &args.bench_out {
+        report.save(path)?;
+    }
+    if let Some(path) = &args.baseline {
+        let regressions = report.regressions(&Report::load(path)?, args.threshold / 100.0);
+        if !regressions.is_empty() {
+            println!();
+            println!("Regressions against {}:", path.display());
+            for regression in &regressions {
+                println!("{regression}");
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,112 @@
+use crate::bench::nanos;
+use crate::solution::{Answers, Timings};
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+
+/// One part's answer as it shows up in the JSON output.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PartOutput {
+    pub answer: String,
+    #[serde(rename = "time_ns", with = "nanos")]
+    pub time: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<Value>,
+}
+
+/// Everything there is to know about running one day, as printed by `aoc --json`, one document
+/// per line:
+///
+/// ```json
+/// {"day":9,"parse_ns":61000,"part1":{"answer":"207","time_ns":9000,"artifacts":{"route":[...]}},...}
+/// {"day":20,"error":"..."}
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum DayOutput {
+    Solved {
+        day: u8,
+        #[serde(rename = "parse_ns", with = "nanos")]
+        parse: Duration,
+        part1: PartOutput,
+        part2: PartOutput,
+    },
+    Failed {
+        day: u8,
+        error: String,
+    },
+}
+
+impl DayOutput {
+    pub fn new(day: u8, result: anyhow::Result<(Answers, Timings)>) -> Self {
+        match result {
+            Ok((answers, timings)) => {
+                let [artifacts1, artifacts2] = answers.artifacts;
+                DayOutput::Solved {
+                    day,
+                    parse: timings.parse,
+                    part1: PartOutput {
+                        answer: answers.part1,
+                        time: timings.part1,
+                        artifacts: artifacts1,
+                    },
+                    part2: PartOutput {
+                        answer: answers.part2,
+                        time: timings.part2,
+                        artifacts: artifacts2,
+                    },
+                }
+            }
+            Err(e) => DayOutput::Failed {
+                day,
+                error: format!("{e:#}"),
+            },
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, DayOutput::Failed { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    #[test]
+    fn test_solved() {
+        let answers = Answers {
+            part1: "207".to_string(),
+            part2: "804".to_string(),
+            artifacts: [Some(json!({ "route": ["A", "B"] })), None],
+        };
+        let timings = Timings {
+            parse: Duration::from_nanos(3),
+            part1: Duration::from_nanos(5),
+            part2: Duration::from_nanos(7),
+        };
+        let output = DayOutput::new(9, Ok((answers, timings)));
+        assert!(!output.is_failed());
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            json!({
+                "day": 9,
+                "parse_ns": 3,
+                "part1": { "answer": "207", "time_ns": 5, "artifacts": { "route": ["A", "B"] } },
+                "part2": { "answer": "804", "time_ns": 7 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_failed() {
+        let output = DayOutput::new(20, Err(anyhow!("no house").context("part 1")));
+        assert!(output.is_failed());
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            json!({ "day": 20, "error": "part 1: no house" })
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use crate::error::Expected;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{alpha1, digit1, one_of, space0};
+use nom::combinator::{eof, map_res, not, opt, peek, recognize};
+use nom::error::{ErrorKind, FromExternalError, ParseError};
+use nom::sequence::{delimited, preceded, terminated};
+use nom::{IResult, Parser};
+use std::borrow::Cow;
+use std::str::FromStr;
+
+/// What the parsers in here return.
+pub type PResult<'a, O> = IResult<&'a str, O, Error<'a>>;
+
+/// A nom error that remembers what the parser expected to see, so that it can be turned into a
+/// helpful [Expected]. When [nom::branch::alt] fails, the error that got furthest into the input
+/// wins, and if several got equally far, their expectations are joined with "or".
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error<'a> {
+    /// The rest of the input, where the parser failed.
+    pub input: &'a str,
+    pub expected: Cow<'static, str>,
+}
+
+impl<'a> Error<'a> {
+    pub fn new(input: &'a str, expected: impl Into<Cow<'static, str>>) -> Self {
+        Error {
+            input,
+            expected: expected.into(),
+        }
+    }
+
+    /// Where in `s` this happened, where `s` is the text the parser was run on.
+    pub fn into_expected(self, s: &str) -> Expected {
+        Expected::new(s.len() - self.input.len(), self.expected)
+    }
+}
+
+impl<'a> ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Error::new(input, describe(kind))
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal if self.expected == other.expected => self,
+            std::cmp::Ordering::Equal => Error::new(
+                self.input,
+                format!("{} or {}", self.expected, other.expected),
+            ),
+        }
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _: E) -> Self {
+        Error::from_error_kind(input, kind)
+    }
+}
+
+fn describe(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::Char => "a different character",
+        ErrorKind::Digit => "a number",
+        ErrorKind::Alpha => "a letter",
+        ErrorKind::AlphaNumeric => "a letter or digit",
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::Eof => "the end of the line",
+        _ => "something else",
+    }
+}
+
+/// Says what `parser` expects, in case it fails before consuming anything.
+pub fn expect<'a, O>(
+    what: &'static str,
+    mut parser: impl Parser<&'a str, Output = O, Error = Error<'a>>,
+) -> impl Parser<&'a str, Output = O, Error = Error<'a>> {
+    move |i: &'a str| match parser.parse(i) {
+        Err(nom::Err::Error(e)) if e.input.len() == i.len() => {
+            Err(nom::Err::Error(Error::new(i, what)))
+        }
+        result => result,
+    }
+}
+
+/// Exactly `t`.
+pub fn token<'a>(t: &'static str) -> impl Parser<&'a str, Output = &'a str, Error = Error<'a>> {
+    move |i: &'a str| {
+        tag(t)
+            .parse(i)
+            .map_err(|e: nom::Err<Error>| e.map(|_| Error::new(i, format!("`{t}`"))))
+    }
+}
+
+/// Like [token], but it can't be followed by more letters, so `AND` doesn't match `ANDY`.
+pub fn keyword<'a>(t: &'static str) -> impl Parser<&'a str, Output = &'a str, Error = Error<'a>> {
+    terminated(token(t), not(peek(alpha1)))
+}
+
+/// Skips spaces and tabs before `inner`.
+pub fn ws<I, O, E: ParseError<I>, G>(inner: G) -> impl Parser<I, Output = O, Error = E>
+where
+    G: Parser<I, Output = O, Error = E>,
+    I: nom::Input,
+    <I as nom::Input>::Item: nom::AsChar,
+{
+    preceded(space0, inner)
+}
+
+/// Skips spaces and tabs on both sides of `inner`.
+pub fn spaced<I, O, E: ParseError<I>, G>(inner: G) -> impl Parser<I, Output = O, Error = E>
+where
+    G: Parser<I, Output = O, Error = E>,
+    I: nom::Input,
+    <I as nom::Input>::Item: nom::AsChar,
+{
+    delimited(space0, inner, space0)
+}
+
+/// A decimal integer with an optional sign, like `-7` or `+42`.
+pub fn int<T: FromStr>(i: &str) -> PResult<'_, T> {
+    expect(
+        "a number",
+        map_res(recognize((opt(one_of("+-")), digit1)), str::parse),
+    )
+    .parse(i)
+}
+
+/// A decimal integer without a sign.
+pub fn uint<T: FromStr>(i: &str) -> PResult<'_, T> {
+    expect("a number", map_res(digit1, str::parse)).parse(i)
+}
+
+/// A name made of letters, digits and underscores, like `Dancer` or `wire_7`.
+pub fn ident(i: &str) -> PResult<'_, &str> {
+    expect(
+        "a name",
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+    )
+    .parse(i)
+}
+
+/// One or more letters.
+pub fn letters(i: &str) -> PResult<'_, &str> {
+    expect("letters", alpha1).parse(i)
+}
+
+/// Runs `parser` on all of `s`, which may only have spaces and tabs left over.
+pub fn parse_all<'a, O>(
+    s: &'a str,
+    parser: impl Parser<&'a str, Output = O, Error = Error<'a>>,
+) -> Result<O, Expected> {
+    terminated(parser, (space0, expect("the end of the line", eof)))
+        .parse_complete(s)
+        .map(|(_, o)| o)
+        .map_err(|e| match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.into_expected(s),
+            nom::Err::Incomplete(_) => Expected::end(s, "more input"),
+        })
+}
+
+/// Parses every line of `s` that isn't blank with [parse_all]. Leading whitespace is skipped.
+pub fn lines<'a, O>(
+    s: &'a str,
+    mut parser: impl Parser<&'a str, Output = O, Error = Error<'a>>,
+) -> Result<Vec<O>, Expected> {
+    s.lines()
+        .map(str::trim_start)
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_all(line, |i| parser.parse(i)).map_err(|e| e.within(s, line)))
+        .collect()
+}
+
+/// Implements [FromStr] for a type with a nom parser from [crate::parse], which has to consume
+/// the whole string:
+///
+/// ```
+/// use aoc2015::parse::{PResult, int, token};
+/// use nom::Parser;
+///
+/// struct Meters(u32);
+///
+/// fn meters(i: &str) -> PResult<'_, Meters> {
+///     (int, token("m")).map(|(m, _)| Meters(m)).parse(i)
+/// }
+///
+/// aoc2015::from_str_via_nom!(Meters, meters);
+///
+/// assert_eq!("42m".parse::<Meters>().unwrap().0, 42);
+/// assert!("42 m".parse::<Meters>().is_err());
+/// ```
+#[macro_export]
+macro_rules! from_str_via_nom {
+    ($t:ty, $parser:expr) => {
+        impl ::std::str::FromStr for $t {
+            type Err = $crate::error::Expected;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $crate::parse::parse_all(s, $parser)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::branch::alt;
+    use nom::combinator::map;
+
+    #[derive(Debug, PartialEq)]
+    struct Pair(String, i32);
+
+    fn pair(i: &str) -> PResult<'_, Pair> {
+        map((ident, ws(token("=")), ws(int)), |(k, _, v)| {
+            Pair(k.to_string(), v)
+        })
+        .parse(i)
+    }
+
+    from_str_via_nom!(Pair, pair);
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(int::<i32>("-7 rest"), Ok((" rest", -7)));
+        assert_eq!(int::<i32>("+42"), Ok(("", 42)));
+        assert_eq!(
+            uint::<u8>("300"),
+            Err(nom::Err::Error(Error::new("300", "a number")))
+        );
+        assert_eq!(ident("wire_7 x"), Ok((" x", "wire_7")));
+        assert_eq!(letters("ab1"), Ok(("1", "ab")));
+        assert_eq!(keyword("AND").parse("AND x"), Ok((" x", "AND")));
+        assert!(keyword("AND").parse("ANDY").is_err());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!("a = 5  ".parse(), Ok(Pair("a".to_string(), 5)));
+        assert_eq!(
+            "a = 5 6".parse::<Pair>(),
+            Err(Expected::new(6, "the end of the line"))
+        );
+        assert_eq!("a 5".parse::<Pair>(), Err(Expected::new(2, "`=`")));
+        assert_eq!("a =".parse::<Pair>(), Err(Expected::new(3, "a number")));
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let mut op = alt((keyword("on"), keyword("off")));
+        assert_eq!(
+            op.parse("of"),
+            Err(nom::Err::Error(Error::new("of", "`on` or `off`")))
+        );
+        // The alternative that got further wins.
+        let mut p = alt((
+            map((token("a"), token("b")), |_| ()),
+            map(token("c"), |_| ()),
+        ));
+        assert_eq!(p.parse("ax"), Err(nom::Err::Error(Error::new("x", "`b`"))));
+    }
+
+    #[test]
+    fn test_lines() {
+        let s = "a = 1\n\n  b = x\n";
+        assert_eq!(lines(s, pair), Err(Expected::new(13, "a number")));
+        assert_eq!(
+            lines("a = 1\n b = 2\n", pair),
+            Ok(vec![Pair("a".to_string(), 1), Pair("b".to_string(), 2)])
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use indicatif::ProgressBar;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Something that wants to hear how far a long computation has got, like a progress bar.
+/// Functions that can take a while accept one, so that they can also run headless.
+pub trait Progress {
+    /// Starts over, with `total` steps to go.
+    fn start(&self, total: u64);
+
+    /// How many steps are done.
+    fn set_position(&self, position: u64);
+
+    fn finish(&self);
+}
+
+/// Ignores everything.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn start(&self, _: u64) {}
+
+    fn set_position(&self, _: u64) {}
+
+    fn finish(&self) {}
+}
+
+/// A progress bar on the terminal.
+#[derive(Clone, Debug)]
+pub struct Bar(ProgressBar);
+
+impl Bar {
+    pub fn new() -> Self {
+        Bar(ProgressBar::new(0))
+    }
+}
+
+impl Default for Bar {
+    fn default() -> Self {
+        Bar::new()
+    }
+}
+
+impl Progress for Bar {
+    fn start(&self, total: u64) {
+        self.0.set_length(total);
+        self.0.set_position(0);
+    }
+
+    fn set_position(&self, position: u64) {
+        self.0.set_position(position);
+    }
+
+    fn finish(&self) {
+        self.0.finish();
+    }
+}
+
+/// Calls a function with the position and total every time the position changes, and once more
+/// when finished.
+pub struct Callback<F> {
+    f: F,
+    total: AtomicU64,
+    position: AtomicU64,
+}
+
+impl<F: Fn(u64, u64)> Callback<F> {
+    pub fn new(f: F) -> Self {
+        Callback {
+            f,
+            total: AtomicU64::new(0),
+            position: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<F: Fn(u64, u64)> Progress for Callback<F> {
+    fn start(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+        self.position.store(0, Ordering::Relaxed);
+    }
+
+    fn set_position(&self, position: u64) {
+        if self.position.swap(position, Ordering::Relaxed) != position {
+            (self.f)(position, self.total.load(Ordering::Relaxed));
+        }
+    }
+
+    fn finish(&self) {
+        (self.f)(
+            self.position.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        );
+    }
+}
+
+static TERMINAL: AtomicBool = AtomicBool::new(false);
+
+/// Lets [terminal] show progress bars. They are off by default, so that tests and library users
+/// stay quiet.
+pub fn enable_terminal(enabled: bool) {
+    TERMINAL.store(enabled, Ordering::Relaxed);
+}
+
+/// What the days use when they are run through [crate::solution::Solution]: a [Bar] if
+/// [enable_terminal] was called, and [NoProgress] otherwise.
+pub fn terminal() -> Box<dyn Progress> {
+    if TERMINAL.load(Ordering::Relaxed) {
+        Box::new(Bar::new())
+    } else {
+        Box::new(NoProgress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_callback() {
+        let calls = RefCell::new(Vec::new());
+        let progress = Callback::new(|position, total| calls.borrow_mut().push((position, total)));
+        progress.start(10);
+        progress.set_position(3);
+        progress.set_position(3);
+        progress.set_position(7);
+        progress.finish();
+        assert_eq!(calls.into_inner(), vec![(3, 10), (7, 10), (7, 10)]);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::days::{d07, d11, d18, d19, d22, d23};
+use crate::input::PuzzleInput;
+use crate::runner::isolate;
+use crate::solution::{Day, Part};
+use anyhow::{Context, bail};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Something to do with a day's state in the REPL, like `eval a` in day 7. Returns what to print.
+pub struct Command<S> {
+    pub name: &'static str,
+    /// The arguments for the help, like `WIRE [VALUE]`.
+    pub args: &'static str,
+    pub help: &'static str,
+    pub run: fn(&mut S, &[&str]) -> anyhow::Result<String>,
+}
+
+/// A day that can be poked at in the REPL: some state made from the input once, and commands that
+/// look at it or change it.
+pub trait Explore: Sized + 'static {
+    const COMMANDS: &'static [Command<Self>];
+
+    fn load(input: &PuzzleInput) -> anyhow::Result<Self>;
+}
+
+/// An [Explore] with its type erased, so that the REPL can hold any day's state.
+pub trait Session {
+    /// The name, arguments and help of every command.
+    fn commands(&self) -> Vec<(&'static str, &'static str, &'static str)>;
+
+    /// Runs the command `name`, or returns `None` if there is no such command.
+    fn call(&mut self, name: &str, args: &[&str]) -> Option<anyhow::Result<String>>;
+}
+
+impl<S: Explore> Session for S {
+    fn commands(&self) -> Vec<(&'static str, &'static str, &'static str)> {
+        S::COMMANDS
+            .iter()
+            .map(|c| (c.name, c.args, c.help))
+            .collect()
+    }
+
+    fn call(&mut self, name: &str, args: &[&str]) -> Option<anyhow::Result<String>> {
+        let command = S::COMMANDS.iter().find(|c| c.name == name)?;
+        Some((command.run)(self, args))
+    }
+}
+
+/// The commands of one day, picked by number at runtime like [Day].
+#[derive(Copy, Clone, Debug)]
+pub struct Explorer {
+    pub day: u8,
+    pub load: fn(&PuzzleInput) -> anyhow::Result<Box<dyn Session>>,
+}
+
+fn load<S: Explore>(input: &PuzzleInput) -> anyhow::Result<Box<dyn Session>> {
+    Ok(Box::new(S::load(input)?))
+}
+
+impl Explorer {
+    pub const fn of<S: Explore>(day: u8) -> Self {
+        Explorer {
+            day,
+            load: load::<S>,
+        }
+    }
+}
+
+pub const EXPLORERS: [Explorer; 6] = [
+    Explorer::of::<d07::Circuit>(7),
+    Explorer::of::<d11::Passwords>(11),
+    Explorer::of::<d18::Life>(18),
+    Explorer::of::<d19::Chemistry>(19),
+    Explorer::of::<d22::Duel>(22),
+    Explorer::of::<d23::Debugger>(23),
+];
+
+pub fn get(day: u8) -> Option<&'static Explorer> {
+    EXPLORERS.iter().find(|e| e.day == day)
+}
+
+/// The argument at `index`, parsed.
+pub fn arg<T: FromStr>(args: &[&str], index: usize, name: &str) -> anyhow::Result<T> {
+    let arg = args.get(index).with_context(|| format!("missing {name}"))?;
+    arg.parse()
+        .ok()
+        .with_context(|| format!("invalid {name} `{arg}`"))
+}
+
+/// Like [arg], but with a default for when it is left out.
+pub fn arg_or<T: FromStr>(
+    args: &[&str],
+    index: usize,
+    name: &str,
+    default: T,
+) -> anyhow::Result<T> {
+    if args.len() > index {
+        arg(args, index, name)
+    } else {
+        Ok(default)
+    }
+}
+
+/// A day's input, loaded once, along with the day's state if it has commands. Lines are run
+/// with [Repl::eval].
+pub struct Repl {
+    day: Day,
+    input: PuzzleInput,
+    session: Option<Box<dyn Session>>,
+}
+
+impl Repl {
+    pub fn new(day: Day, input: PuzzleInput) -> anyhow::Result<Self> {
+        let session = get(day.day).map(|e| (e.load)(&input)).transpose()?;
+        Ok(Repl {
+            day,
+            input,
+            session,
+        })
+    }
+
+    /// Runs one line, like `step 10`, and returns what to print. Besides the day's own commands,
+    /// there are `help`, `part1` and `part2` for every day. A panic becomes an error, so that the
+    /// REPL can go on.
+    pub fn eval(&mut self, line: &str) -> anyhow::Result<String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        match name {
+            "help" => Ok(self.help()),
+            "part1" => isolate(|| self.day.run_part(&self.input, Part::One)),
+            "part2" => isolate(|| self.day.run_part(&self.input, Part::Two)),
+            _ => match self.session.as_mut() {
+                Some(session) => isolate(|| {
+                    session
+                        .call(name, args)
+                        .with_context(|| format!("unknown command `{name}`, try `help`"))?
+                }),
+                None => bail!("unknown command `{name}`, try `help`"),
+            },
+        }
+    }
+
+    pub fn help(&self) -> String {
+        let mut commands = vec![
+            ("help", "", "Shows this."),
+            ("part1", "", "Solves part 1."),
+            ("part2", "", "Solves part 2."),
+        ];
+        commands.extend(self.session.iter().flat_map(|s| s.commands()));
+        commands.push(("quit", "", "Leaves the REPL."));
+        let mut help = String::new();
+        for (name, args, text) in commands {
+            let _ = writeln!(help, "{:<20} {text}", format!("{name} {args}"));
+        }
+        help.pop();
+        help
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+
+    fn repl(day: u8, text: &str) -> Repl {
+        let day = *days::get(day).unwrap();
+        Repl::new(day, PuzzleInput::new(text, "")).unwrap()
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut repl = repl(11, "abcdefgh\n");
+        assert_eq!(repl.eval("safe").unwrap(), "false");
+        assert_eq!(repl.eval("safe abcdffaa").unwrap(), "true");
+        assert_eq!(repl.eval("part1").unwrap(), "abcdffaa");
+        assert_eq!(repl.eval("   ").unwrap(), "");
+        assert_eq!(
+            repl.eval("fly").unwrap_err().to_string(),
+            "unknown command `fly`, try `help`"
+        );
+        assert!(repl.help().contains("next [PASSWORD]"));
+    }
+
+    struct Broken;
+
+    impl Explore for Broken {
+        const COMMANDS: &'static [Command<Self>] = &[Command {
+            name: "break",
+            args: "",
+            help: "Panics.",
+            run: |_, _| panic!("broken"),
+        }];
+
+        fn load(_: &PuzzleInput) -> anyhow::Result<Self> {
+            Ok(Broken)
+        }
+    }
+
+    #[test]
+    fn test_panicking_command() {
+        let mut repl = repl(11, "abcdefgh\n");
+        repl.session = Some(Box::new(Broken));
+        assert_eq!(
+            repl.eval("break").unwrap_err().to_string(),
+            "panicked: broken"
+        );
+        assert_eq!(repl.eval("part1").unwrap(), "abcdffaa");
+        assert_eq!(
+            repl.eval("safe").unwrap_err().to_string(),
+            "unknown command `safe`, try `help`"
+        );
+    }
+
+    #[test]
+    fn test_days_without_commands() {
+        let mut repl = repl(1, "(()(\n");
+        assert_eq!(repl.eval("part1").unwrap(), "2");
+        assert!(repl.eval("step").is_err());
+        assert!(!repl.help().contains("step"));
+    }
+
+    #[test]
+    fn test_args() {
+        assert_eq!(arg::<u16>(&["7"], 0, "count").unwrap(), 7);
+        assert_eq!(
+            arg::<u16>(&[], 0, "count").unwrap_err().to_string(),
+            "missing count"
+        );
+        assert_eq!(
+            arg::<u16>(&["x"], 0, "count").unwrap_err().to_string(),
+            "invalid count `x`"
+        );
+        assert_eq!(arg_or(&[], 0, "count", 1).unwrap(), 1);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::solution::{Answers, Day, Timings};
+use anyhow::anyhow;
+use std::any::Any;
+use std::fmt;
+use std::fmt::Formatter;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What came out of running one day, including how long it took from loading the input to the
+/// last answer.
+#[derive(Debug)]
+pub struct DayResult {
+    pub day: u8,
+    pub result: anyhow::Result<(Answers, Timings)>,
+    pub elapsed: Duration,
+}
+
+/// Runs `f`, turning a panic into an error, so that one broken day doesn't take the others down
+/// with it.
+pub fn isolate<T>(f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(anyhow!("panicked: {}", panic_message(payload.as_ref()))))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "unknown cause"
+    }
+}
+
+/// The number of threads to use when none was asked for.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Runs `run` for every day on up to `jobs` threads, with panics isolated per day. The results
+/// are in the same order as `days`, no matter which finished first.
+pub fn run_days<F>(days: &[&Day], jobs: usize, run: F) -> Vec<DayResult>
+where
+    F: Fn(&Day) -> anyhow::Result<(Answers, Timings)> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(days.len()));
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let before = Instant::now();
+                    let result = isolate(|| run(day));
+                    let result = DayResult {
+                        day: day.day,
+                        result,
+                        elapsed: before.elapsed(),
+                    };
+                    results.lock().unwrap().push(result);
+                }
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|r| days.iter().position(|d| d.day == r.day));
+    results
+}
+
+/// A table with the answers and run time of every day, followed by the totals.
+pub struct Summary<'a> {
+    pub results: &'a [DayResult],
+    /// How long running all of them took, which is less than the sum when they ran in parallel.
+    pub wall_time: Duration,
+}
+
+impl Summary<'_> {
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|r| r.result.is_err()).count()
+    }
+}
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<4} {:<20} {:<20} {:>12}",
+            "Day", "Part 1", "Part 2", "Time"
+        )?;
+        for r in self.results {
+            let day = format!("{:02}", r.day);
+            let time = format!("{:.2?}", r.elapsed);
+            match &r.result {
+                Ok((answers, _)) => writeln!(
+                    f,
+                    "{day:<4} {:<20} {:<20} {time:>12}",
+                    answers.part1, answers.part2
+                )?,
+                Err(e) => writeln!(f, "{day:<4} {:<41} {time:>12}", format!("FAILED: {e:#}"))?,
+            }
+        }
+        let total: Duration = self.results.iter().map(|r| r.elapsed).sum();
+        writeln!(f)?;
+        write!(
+            f,
+            "{} solved, {} failed, {:.2?} in total, {:.2?} wall time",
+            self.results.len() - self.failed(),
+            self.failed(),
+            total,
+            self.wall_time
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::PuzzleInput;
+    use crate::solution::Solution;
+
+    struct Panics;
+
+    impl Solution for Panics {
+        type Input = ();
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(_: &PuzzleInput) -> anyhow::Result<Self::Input> {
+            panic!("oops")
+        }
+
+        fn part1(_: &Self::Input) -> anyhow::Result<Self::Part1> {
+            Ok(1)
+        }
+
+        fn part2(_: &Self::Input) -> anyhow::Result<Self::Part2> {
+            Ok(2)
+        }
+    }
+
+    struct Works;
+
+    impl Solution for Works {
+        type Input = u32;
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+            Ok(input.trim().parse()?)
+        }
+
+        fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+            Ok(input + 1)
+        }
+
+        fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+            Ok(input * 2)
+        }
+    }
+
+    #[test]
+    fn test_run_days() {
+        let days = [
+            Day::of::<Works>(1),
+            Day::of::<Panics>(2),
+            Day::of::<Works>(3),
+        ];
+        let days: Vec<_> = days.iter().collect();
+        let results = run_days(&days, 2, |day| {
+            day.run(&PuzzleInput::new(day.day.to_string(), ""))
+        });
+        assert_eq!(
+            results.iter().map(|r| r.day).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(results[2].result.as_ref().unwrap().0.part2, "6");
+        let e = results[1].result.as_ref().unwrap_err();
+        assert_eq!(e.to_string(), "panicked: oops");
+
+        let summary = Summary {
+            results: &results,
+            wall_time: Duration::ZERO,
+        };
+        assert_eq!(summary.failed(), 1);
+        let table = summary.to_string();
+        assert!(
+            table
+                .lines()
+                .nth(2)
+                .unwrap()
+                .starts_with("02   FAILED: panicked: oops")
+        );
+        assert!(
+            table
+                .lines()
+                .last()
+                .unwrap()
+                .starts_with("2 solved, 1 failed, ")
+        );
+    }
+}
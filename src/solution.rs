@@ -0,0 +1,143 @@
+use crate::input::PuzzleInput;
+use serde_json::Value;
+use std::fmt;
+use std::fmt::Formatter;
+use std::time::{Duration, Instant};
+
+/// A single day's puzzle. The input is parsed once and then shared by both parts.
+pub trait Solution {
+    /// The puzzle input after parsing.
+    type Input;
+    type Part1: Answer;
+    type Part2: Answer;
+
+    /// Name of the file in the day's directory that holds the puzzle input.
+    const INPUT_FILE: &'static str = "input.txt";
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1>;
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2>;
+}
+
+/// The answer to one part of a day.
+pub trait Answer: fmt::Display {
+    /// Anything besides the answer itself that is worth keeping, like the route in day 9. Only
+    /// shows up in the JSON output.
+    fn artifacts(&self) -> Option<Value> {
+        None
+    }
+}
+
+macro_rules! plain_answers {
+    ($($t:ty),*) => {
+        $(impl Answer for $t {})*
+    };
+}
+
+plain_answers!(i32, i64, u16, u32, u64, usize, String, NoAnswer);
+
+/// Answer for a part that doesn't have a puzzle, which is only the second half of day 25.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct NoAnswer;
+
+impl fmt::Display for NoAnswer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "-")
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    pub part1: String,
+    pub part2: String,
+    /// The [Answer::artifacts] of both parts.
+    pub artifacts: [Option<Value>; 2],
+}
+
+/// How long each step of solving a day took.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+pub fn solve<S: Solution>(input: &PuzzleInput) -> anyhow::Result<(Answers, Timings)> {
+    let before = Instant::now();
+    let input = S::parse(input)?;
+    let parse = before.elapsed();
+
+    let before = Instant::now();
+    let part1 = S::part1(&input)?;
+    let part1_time = before.elapsed();
+
+    let before = Instant::now();
+    let part2 = S::part2(&input)?;
+    let part2_time = before.elapsed();
+
+    Ok((
+        Answers {
+            part1: part1.to_string(),
+            part2: part2.to_string(),
+            artifacts: [part1.artifacts(), part2.artifacts()],
+        },
+        Timings {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+        },
+    ))
+}
+
+/// One of the two parts of a day.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+/// Parses the input and solves only `part`, for inputs like the examples that only make sense for
+/// one of them.
+pub fn solve_part<S: Solution>(input: &PuzzleInput, part: Part) -> anyhow::Result<String> {
+    let input = S::parse(input)?;
+    Ok(match part {
+        Part::One => S::part1(&input)?.to_string(),
+        Part::Two => S::part2(&input)?.to_string(),
+    })
+}
+
+/// A [Solution] with its types erased, so that days can be picked by number at runtime.
+#[derive(Copy, Clone, Debug)]
+pub struct Day {
+    pub day: u8,
+    pub input_file: &'static str,
+    pub solve: fn(&PuzzleInput) -> anyhow::Result<(Answers, Timings)>,
+    pub solve_part: fn(&PuzzleInput, Part) -> anyhow::Result<String>,
+}
+
+impl Day {
+    pub const fn of<S: Solution>(day: u8) -> Self {
+        Day {
+            day,
+            input_file: S::INPUT_FILE,
+            solve: solve::<S>,
+            solve_part: solve_part::<S>,
+        }
+    }
+
+    pub fn run(&self, input: &PuzzleInput) -> anyhow::Result<(Answers, Timings)> {
+        (self.solve)(input)
+    }
+
+    pub fn run_part(&self, input: &PuzzleInput, part: Part) -> anyhow::Result<String> {
+        (self.solve_part)(input, part)
+    }
+}
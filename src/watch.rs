@@ -0,0 +1,203 @@
+use crate::answers::Verdict;
+use crate::input::{Inputs, Source};
+use crate::runner::isolate;
+use crate::solution::Day;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Notices when files change by looking at their modification time and size every time it is
+/// asked, which is good enough for a handful of puzzle inputs.
+pub struct Watcher {
+    /// Every file directly inside these is watched, except for the solutions themselves, so that
+    /// new examples are picked up too.
+    dirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    seen: BTreeMap<PathBuf, (SystemTime, u64)>,
+}
+
+impl Watcher {
+    pub fn new(dirs: Vec<PathBuf>, files: Vec<PathBuf>) -> Self {
+        let mut watcher = Watcher {
+            dirs,
+            files,
+            seen: BTreeMap::new(),
+        };
+        watcher.seen = watcher.scan();
+        watcher
+    }
+
+    fn scan(&self) -> BTreeMap<PathBuf, (SystemTime, u64)> {
+        let in_dirs = self
+            .dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_none_or(|e| e != "rs"));
+        in_dirs
+            .chain(self.files.iter().cloned())
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok().filter(|m| m.is_file())?;
+                Some((path, (metadata.modified().ok()?, metadata.len())))
+            })
+            .collect()
+    }
+
+    /// The files that were added, changed or removed since the last call, or since the watcher
+    /// was made.
+    pub fn changes(&mut self) -> Vec<PathBuf> {
+        let now = self.scan();
+        let mut changes: Vec<_> = now
+            .iter()
+            .filter(|&(path, state)| self.seen.get(path) != Some(state))
+            .map(|(path, _)| path.clone())
+            .chain(self.seen.keys().filter(|p| !now.contains_key(*p)).cloned())
+            .collect();
+        changes.sort();
+        self.seen = now;
+        changes
+    }
+}
+
+/// What one run over a day's input and examples came up with: an answer or error for each part
+/// of the input, and a verdict for each part of an example that has an expected answer.
+pub type Outcome = Vec<(String, String)>;
+
+/// Solves `day` for its input and all its examples, with panics turned into errors.
+pub fn solve(day: &Day, inputs: &Inputs, source: &Source) -> Outcome {
+    let mut outcome = Vec::new();
+    match isolate(|| inputs.load(day, source).and_then(|input| day.run(&input))) {
+        Ok((answers, _)) => {
+            outcome.push(("input part 1".to_string(), answers.part1));
+            outcome.push(("input part 2".to_string(), answers.part2));
+        }
+        Err(e) => outcome.push(("input".to_string(), format!("error: {e:#}"))),
+    }
+    let examples = match inputs.examples(day.day) {
+        Ok(examples) => examples,
+        Err(e) => {
+            outcome.push(("examples".to_string(), format!("error: {e:#}")));
+            Vec::new()
+        }
+    };
+    for example in examples {
+        let name = format!("examples/{}", example.name);
+        match isolate(|| example.run(day, inputs)) {
+            Ok(verdicts) => {
+                for (part, verdict) in verdicts.iter().enumerate() {
+                    if *verdict != Verdict::Missing {
+                        outcome.push((format!("{name} part {}", part + 1), verdict.to_string()));
+                    }
+                }
+            }
+            Err(e) => outcome.push((name, format!("error: {e:#}"))),
+        }
+    }
+    outcome
+}
+
+/// One line per answer, marking the ones that changed since `previous` with `*` and the ones that
+/// are gone with `-`.
+pub fn diff(previous: Option<&Outcome>, current: &Outcome) -> String {
+    let mut s = String::new();
+    let before = |name: &str| {
+        previous.and_then(|p| p.iter().find(|(n, _)| n == name).map(|(_, a)| a.as_str()))
+    };
+    let mut changed = 0;
+    for (name, answer) in current {
+        match before(name) {
+            Some(was) if was != answer => {
+                changed += 1;
+                let _ = writeln!(s, "* {name}: {answer} (was {was})");
+            }
+            None if previous.is_some() => {
+                changed += 1;
+                let _ = writeln!(s, "* {name}: {answer} (new)");
+            }
+            _ => {
+                let _ = writeln!(s, "  {name}: {answer}");
+            }
+        }
+    }
+    for (name, was) in previous.into_iter().flatten() {
+        if !current.iter().any(|(n, _)| n == name) {
+            changed += 1;
+            let _ = writeln!(s, "- {name}: {was}");
+        }
+    }
+    if previous.is_some() {
+        let _ = writeln!(s, "{changed} changed");
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+    use std::env;
+
+    fn outcome(lines: &[(&str, &str)]) -> Outcome {
+        lines
+            .iter()
+            .map(|&(n, a)| (n.to_string(), a.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let first = outcome(&[("input part 1", "12"), ("examples/a part 1", "pass")]);
+        assert_eq!(
+            diff(None, &first),
+            "  input part 1: 12\n  examples/a part 1: pass\n"
+        );
+        let second = outcome(&[("input part 1", "13"), ("examples/b part 1", "pass")]);
+        assert_eq!(
+            diff(Some(&first), &second),
+            "* input part 1: 13 (was 12)\n\
+             * examples/b part 1: pass (new)\n\
+             - examples/a part 1: pass\n\
+             3 changed\n"
+        );
+        assert_eq!(
+            diff(Some(&second), &second),
+            "  input part 1: 13\n  examples/b part 1: pass\n0 changed\n"
+        );
+    }
+
+    #[test]
+    fn test_watcher() {
+        let dir = env::temp_dir().join(format!("aoc2015-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.txt");
+        fs::write(&input, "1").unwrap();
+        fs::write(dir.join("d01.rs"), "").unwrap();
+
+        let mut watcher = Watcher::new(vec![dir.clone()], Vec::new());
+        assert!(watcher.changes().is_empty());
+        fs::write(&input, "12").unwrap();
+        fs::write(dir.join("d01.rs"), "fn main() {}").unwrap();
+        assert_eq!(watcher.changes(), vec![input.clone()]);
+        assert!(watcher.changes().is_empty());
+        let example = dir.join("example.txt");
+        fs::write(&example, "").unwrap();
+        fs::remove_file(&input).unwrap();
+        assert_eq!(watcher.changes(), vec![example, input]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_solve() {
+        let day = days::get(1).unwrap();
+        let inputs = Inputs::default();
+        let outcome = solve(day, &inputs, &Source::File("/nonexistent".into()));
+        assert_eq!(outcome[0].0, "input");
+        assert!(outcome[0].1.starts_with("error: "));
+        assert!(outcome.len() > 1);
+        assert!(outcome[1..].iter().all(|(_, verdict)| verdict == "pass"));
+    }
+}